@@ -0,0 +1,41 @@
+// ── Build metadata ──
+//
+// Stamps the git commit into `ccs --version` (see cli.rs), e.g.
+// "0.1.0 (abc1234 2024-06-01)". The date is the commit's, so the same
+// checkout always builds the same string. Outside a git checkout, or without
+// git, both read "unknown".
+
+use std::process::Command;
+
+/// Trimmed stdout of `git args`, or None if git is missing or fails.
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let out = String::from_utf8(output.stdout).ok()?;
+    Some(out.trim().to_string()).filter(|s| !s.is_empty())
+}
+
+fn main() {
+    let sha = git(&["rev-parse", "--short=7", "HEAD"]);
+    let date = git(&["log", "-1", "--format=%cd", "--date=short"]);
+    println!(
+        "cargo:rustc-env=CCS_GIT_SHA={}",
+        sha.as_deref().unwrap_or("unknown")
+    );
+    println!(
+        "cargo:rustc-env=CCS_GIT_DATE={}",
+        date.as_deref().unwrap_or("unknown")
+    );
+
+    // Rebuild when HEAD moves: a checkout rewrites HEAD, a commit the branch ref
+    if let Some(git_dir) = git(&["rev-parse", "--git-dir"]) {
+        println!("cargo:rerun-if-changed={git_dir}/HEAD");
+        if let Some(branch) = git(&["symbolic-ref", "-q", "HEAD"]) {
+            println!("cargo:rerun-if-changed={git_dir}/{branch}");
+        }
+        println!("cargo:rerun-if-changed={git_dir}/packed-refs");
+    }
+    println!("cargo:rerun-if-changed=build.rs");
+}
@@ -1,8 +1,27 @@
 use clap::{Parser, Subcommand};
 
+/// `--version` output: the crate version plus the commit it was built from
+/// (see build.rs).
+const VERSION: &str = concat!(
+    env!("CARGO_PKG_VERSION"),
+    " (",
+    env!("CCS_GIT_SHA"),
+    " ",
+    env!("CCS_GIT_DATE"),
+    ")"
+);
+
 #[derive(Parser)]
-#[command(name = "ccs", about = "Claude Code session manager")]
+#[command(name = "ccs", about = "Claude Code session manager", version = VERSION)]
 pub struct Cli {
+    /// Disable colored output (also honors NO_COLOR)
+    #[arg(long, global = true)]
+    pub no_color: bool,
+
+    /// tmux session to manage (default: $CCS_SESSION, or "ccs")
+    #[arg(long = "session", value_name = "NAME", global = true)]
+    pub tmux_session: Option<String>,
+
     #[command(subcommand)]
     pub command: Command,
 }
@@ -16,19 +35,109 @@ pub enum Command {
         name: String,
         /// Working directory
         dir: Option<String>,
+        /// Create the working directory if it doesn't exist
+        #[arg(short = 'p', long)]
+        create_dir: bool,
+        /// Without a directory, choose one from past `ccs start` directories
+        /// with fzf (falls back to "." if fzf isn't installed)
+        #[arg(long, conflicts_with = "dir")]
+        pick_dir: bool,
+        /// Split the current tmux window instead of adding a session tab.
+        /// The pane lives outside the session, so `ccs list` and the sidebar
+        /// don't show it
+        #[arg(long)]
+        here: bool,
+        /// Check BRANCH out in a new git worktree at ../<name> next to the
+        /// repo and start there (the branch is created if it doesn't exist)
+        #[arg(long, value_name = "BRANCH")]
+        worktree: Option<String>,
+        /// When adding to a running session, focus the new window's claude
+        /// pane, switching this client over to the session if needed
+        #[arg(short = 's', long, conflicts_with = "here")]
+        select: bool,
+        /// Launch claude with --continue to resume the last conversation in the directory
+        #[arg(short = 'c', long = "continue")]
+        continue_conversation: bool,
+        /// Arguments passed through to claude, after `--`; the directory can
+        /// be left out (e.g. `ccs start review -- --model opus`)
+        #[arg(last = true, allow_hyphen_values = true, value_name = "CLAUDE_ARGS")]
+        claude_args: Vec<String>,
     },
     /// List active sessions
     #[command(alias = "ls")]
     List,
     /// Kill a single session tab
     Kill {
-        /// Session name to kill
+        /// Session name to kill, or a window index when no session has that name
         name: String,
+        /// Also remove the git worktree the session was working in
+        #[arg(long)]
+        remove_worktree: bool,
+    },
+    /// Exchange the positions of two session tabs
+    Swap {
+        /// First session name
+        a: String,
+        /// Second session name
+        b: String,
     },
     /// Kill all sessions
     AllKill,
+    /// Switch to the next session tab (wraps around)
+    Next,
+    /// Switch to the previous session tab (wraps around)
+    Prev,
+    /// Jump to the first session that's asking a question (or else idle)
+    Focus,
+    /// Print a one-line state summary, e.g. for tmux's `status-right '#(ccs status)'`
+    Status {
+        /// Per-session template; placeholders: {name} {index} {state} {glyph}
+        #[arg(long, default_value = crate::commands::status::DEFAULT_FORMAT)]
+        format: String,
+        /// Text placed between sessions
+        #[arg(long, default_value = " ")]
+        separator: String,
+        /// Print waybar/polybar JSON: {"text", "tooltip", "class"}
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print the number of session tabs, e.g. for a shell prompt
+    Count {
+        /// Only count tabs in this state
+        #[arg(long, value_parser = ["fresh", "working", "asking", "limited", "idle", "done", "compacting"])]
+        state: Option<String>,
+    },
+    /// Show window, pane, and state details for one session tab
+    Info {
+        /// Session name to inspect
+        name: String,
+    },
+    /// Type text into a session tab's claude pane
+    Send {
+        /// Session name to type into
+        name: String,
+        /// Text to type, sent literally
+        text: String,
+        /// Press Enter afterwards to submit it
+        #[arg(long)]
+        enter: bool,
+    },
+    /// Type the same text into every session tab running Claude
+    Broadcast {
+        /// Text to type, sent literally
+        text: String,
+        /// Press Enter afterwards to submit it
+        #[arg(long)]
+        enter: bool,
+    },
+    /// Print directories used with `ccs start`, newest first
+    Recent,
     /// Reattach to existing session
-    Resume,
+    Resume {
+        /// Choose which session tab to land on from a numbered list
+        #[arg(long)]
+        pick: bool,
+    },
     /// Interactive session navigator (launched by start)
     Sidebar,
     /// Handle Claude Code hook events (called by hooks, not directly)
@@ -37,7 +146,51 @@ pub enum Command {
         event: HookEvent,
     },
     /// Install Claude Code hooks for session status detection
-    Init,
+    Init {
+        /// Print the resulting settings.json instead of writing it
+        #[arg(long)]
+        dry_run: bool,
+        /// Reinstall even if hooks exist (repoints stale paths, applies timeout/async)
+        #[arg(long)]
+        force: bool,
+        /// Install into ./.claude/settings.json instead of ~/.claude/settings.json
+        #[arg(long, conflicts_with = "path")]
+        project: bool,
+        /// Install into <DIR>/.claude/settings.json
+        #[arg(long, value_name = "DIR")]
+        path: Option<String>,
+        /// Seconds Claude Code waits for each hook (default: hooks.timeout from config, or 5).
+        /// Updates hooks that are already installed
+        #[arg(long, value_name = "SECS")]
+        timeout: Option<u64>,
+        /// Run hooks synchronously instead of async. Updates hooks that are
+        /// already installed
+        #[arg(long)]
+        no_async: bool,
+    },
+    /// Print recorded hook events (all sessions merged by time if none given)
+    Events {
+        /// Claude session id (event file name without .jsonl)
+        session: Option<String>,
+        /// Keep printing new events as they're written
+        #[arg(short, long)]
+        follow: bool,
+    },
+    /// Remove event files for Claude sessions that no longer exist
+    Prune {
+        /// Also remove event files not written to in this many days
+        #[arg(long, value_name = "DAYS")]
+        older_than: Option<u64>,
+    },
+    /// Print the man page as roff, for packagers (`ccs man > ccs.1`)
+    #[command(hide = true)]
+    Man,
+    /// Check that hooks, directories, and tmux are set up correctly
+    Doctor {
+        /// Repair problems that can be fixed safely (asks before each fix)
+        #[arg(long)]
+        fix: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -51,3 +204,26 @@ pub enum HookEvent {
     /// User answered an AskUserQuestion (PostToolUse hook)
     AskDone,
 }
+
+// ── Tests ──
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_start_claude_args_after_double_dash() {
+        let claude_args = |args: &[&str]| match Cli::parse_from(args).command {
+            Command::Start {
+                dir, claude_args, ..
+            } => (dir, claude_args),
+            _ => unreachable!(),
+        };
+        let (dir, args) = claude_args(&["ccs", "start", "review", "--", "--model", "opus"]);
+        assert_eq!(dir, None);
+        assert_eq!(args, ["--model", "opus"]);
+        let (dir, args) = claude_args(&["ccs", "start", "review", "~/src", "--", "-c"]);
+        assert_eq!(dir.as_deref(), Some("~/src"));
+        assert_eq!(args, ["-c"]);
+    }
+}
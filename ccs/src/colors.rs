@@ -1,22 +1,212 @@
-// ── Catppuccin Mocha palette ──
+// ── Color themes ──
+//
+// The sidebar and CLI output share one palette, chosen by `$CCS_THEME` or
+// `theme = "..."` in ~/.ccs/config.toml. Catppuccin Mocha is the default.
+// CLI escapes are dropped entirely under `NO_COLOR` or `--no-color`.
+
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use ratatui::style::Color;
 
-pub const PEACH: Color = Color::Rgb(250, 179, 135);
-pub const LAVENDER: Color = Color::Rgb(180, 190, 254);
-pub const BLUE: Color = Color::Rgb(137, 180, 250);
-pub const SUBTEXT: Color = Color::Rgb(166, 173, 200);
-pub const OVERLAY: Color = Color::Rgb(108, 112, 134);
-pub const SURFACE: Color = Color::Rgb(69, 71, 90);
-pub const GREEN: Color = Color::Rgb(166, 227, 161);
-pub const RED: Color = Color::Rgb(243, 139, 168);
-
-// ── ANSI escape codes for non-ratatui output (CLI commands) ──
-
-pub const ANSI_PEACH: &str = "\x1b[38;2;250;179;135m";
-pub const ANSI_BLUE: &str = "\x1b[38;2;137;180;250m";
-pub const ANSI_OVERLAY: &str = "\x1b[38;2;108;112;134m";
-pub const ANSI_SURFACE: &str = "\x1b[38;2;69;71;90m";
-pub const ANSI_SUBTEXT: &str = "\x1b[38;2;166;173;200m";
-pub const ANSI_BOLD: &str = "\x1b[1m";
-pub const ANSI_RESET: &str = "\x1b[0m";
+use crate::config::Config;
+
+// ── Types ──
+
+/// Named colors used across the UI. Field names follow the Catppuccin roles;
+/// other presets map their closest equivalents onto them.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Theme {
+    pub peach: Color,
+    pub lavender: Color,
+    pub blue: Color,
+    pub subtext: Color,
+    pub overlay: Color,
+    pub surface: Color,
+    pub green: Color,
+    pub yellow: Color,
+    pub red: Color,
+}
+
+/// ANSI escape codes for non-ratatui output (CLI commands).
+pub struct Ansi {
+    pub peach: String,
+    pub overlay: String,
+    pub surface: String,
+    pub subtext: String,
+    pub green: String,
+    pub yellow: String,
+    pub red: String,
+    pub bold: &'static str,
+    pub reset: &'static str,
+}
+
+static DISABLED: AtomicBool = AtomicBool::new(false);
+
+// ── Presets ──
+
+pub const MOCHA: Theme = Theme {
+    peach: Color::Rgb(250, 179, 135),
+    lavender: Color::Rgb(180, 190, 254),
+    blue: Color::Rgb(137, 180, 250),
+    subtext: Color::Rgb(166, 173, 200),
+    overlay: Color::Rgb(108, 112, 134),
+    surface: Color::Rgb(69, 71, 90),
+    green: Color::Rgb(166, 227, 161),
+    yellow: Color::Rgb(249, 226, 175),
+    red: Color::Rgb(243, 139, 168),
+};
+
+pub const LATTE: Theme = Theme {
+    peach: Color::Rgb(254, 100, 11),
+    lavender: Color::Rgb(114, 135, 253),
+    blue: Color::Rgb(30, 102, 245),
+    subtext: Color::Rgb(108, 111, 133),
+    overlay: Color::Rgb(156, 160, 176),
+    surface: Color::Rgb(188, 192, 204),
+    green: Color::Rgb(64, 160, 43),
+    yellow: Color::Rgb(223, 142, 29),
+    red: Color::Rgb(210, 15, 57),
+};
+
+pub const GRUVBOX: Theme = Theme {
+    peach: Color::Rgb(254, 128, 25),
+    lavender: Color::Rgb(211, 134, 155),
+    blue: Color::Rgb(131, 165, 152),
+    subtext: Color::Rgb(189, 174, 147),
+    overlay: Color::Rgb(146, 131, 116),
+    surface: Color::Rgb(80, 73, 69),
+    green: Color::Rgb(184, 187, 38),
+    yellow: Color::Rgb(250, 189, 47),
+    red: Color::Rgb(251, 73, 52),
+};
+
+pub const NORD: Theme = Theme {
+    peach: Color::Rgb(208, 135, 112),
+    lavender: Color::Rgb(180, 142, 173),
+    blue: Color::Rgb(136, 192, 208),
+    subtext: Color::Rgb(216, 222, 233),
+    overlay: Color::Rgb(97, 110, 136),
+    surface: Color::Rgb(67, 76, 94),
+    green: Color::Rgb(163, 190, 140),
+    yellow: Color::Rgb(235, 203, 139),
+    red: Color::Rgb(191, 97, 106),
+};
+
+// ── Public API ──
+
+impl Theme {
+    /// Look up a preset by name (case-insensitive).
+    pub fn by_name(name: &str) -> Option<Theme> {
+        match name.to_ascii_lowercase().as_str() {
+            "mocha" => Some(MOCHA),
+            "latte" => Some(LATTE),
+            "gruvbox" => Some(GRUVBOX),
+            "nord" => Some(NORD),
+            _ => None,
+        }
+    }
+
+    /// Resolve the active theme: `$CCS_THEME`, then the config file, then Mocha.
+    /// Unknown names warn on stderr and fall back to Mocha.
+    pub fn load() -> Theme {
+        let name = std::env::var("CCS_THEME")
+            .ok()
+            .filter(|s| !s.is_empty())
+            .or_else(|| Config::load().theme);
+        let Some(name) = name else {
+            return MOCHA;
+        };
+        Theme::by_name(&name).unwrap_or_else(|| {
+            eprintln!("ccs: unknown theme '{name}', using mocha");
+            MOCHA
+        })
+    }
+
+    pub fn ansi(&self) -> Ansi {
+        Ansi {
+            peach: fg(self.peach),
+            overlay: fg(self.overlay),
+            surface: fg(self.surface),
+            subtext: fg(self.subtext),
+            green: fg(self.green),
+            yellow: fg(self.yellow),
+            red: fg(self.red),
+            bold: "\x1b[1m",
+            reset: "\x1b[0m",
+        }
+    }
+}
+
+impl Ansi {
+    /// Every escape empty, so formatted output comes out as plain text.
+    pub fn plain() -> Ansi {
+        Ansi {
+            peach: String::new(),
+            overlay: String::new(),
+            surface: String::new(),
+            subtext: String::new(),
+            green: String::new(),
+            yellow: String::new(),
+            red: String::new(),
+            bold: "",
+            reset: "",
+        }
+    }
+}
+
+/// Turn off CLI colors for the rest of the process (`--no-color`).
+pub fn disable() {
+    DISABLED.store(true, Ordering::Relaxed);
+}
+
+/// ANSI codes for the active theme, or plain text when color is disabled.
+pub fn ansi() -> Ansi {
+    if color_disabled(std::env::var_os("NO_COLOR").as_deref()) {
+        return Ansi::plain();
+    }
+    Theme::load().ansi()
+}
+
+// ── Helpers ──
+
+/// The `--no-color` flag, or `NO_COLOR` set to any non-empty value (no-color.org).
+fn color_disabled(no_color: Option<&std::ffi::OsStr>) -> bool {
+    DISABLED.load(Ordering::Relaxed) || no_color.is_some_and(|v| !v.is_empty())
+}
+
+/// Truecolor foreground escape for an RGB color; other colors map to the
+/// terminal default since every preset is RGB.
+fn fg(color: Color) -> String {
+    match color {
+        Color::Rgb(r, g, b) => format!("\x1b[38;2;{r};{g};{b}m"),
+        _ => "\x1b[39m".to_string(),
+    }
+}
+
+// ── Tests ──
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_by_name_is_case_insensitive() {
+        assert_eq!(Theme::by_name("Nord"), Some(NORD));
+        assert_eq!(Theme::by_name("gruvbox"), Some(GRUVBOX));
+        assert_eq!(Theme::by_name("solarized"), None);
+    }
+
+    #[test]
+    fn test_ansi_matches_mocha_palette() {
+        let ansi = MOCHA.ansi();
+        assert_eq!(ansi.peach, "\x1b[38;2;250;179;135m");
+        assert_eq!(ansi.red, "\x1b[38;2;243;139;168m");
+    }
+
+    #[test]
+    fn test_no_color_requires_non_empty_value() {
+        assert!(color_disabled(Some("1".as_ref())));
+        assert!(!color_disabled(Some("".as_ref())));
+        assert!(!color_disabled(None));
+    }
+}
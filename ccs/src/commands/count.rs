@@ -0,0 +1,29 @@
+// ── Session count for shell prompts ──
+//
+// Prints a single integer and always exits 0, even with no session, so it can
+// sit in a prompt: `$(ccs count --state asking)` -> "2". Without --state no
+// detect pass runs; it's just one list-panes call.
+
+use crate::sidebar::state::StateDetector;
+use crate::tmux;
+
+// ── Public API ──
+
+pub fn run(session: &str, state: Option<&str>) -> Result<(), String> {
+    if !tmux::has_session(session) {
+        println!("0");
+        return Ok(());
+    }
+
+    let windows = tmux::list_windows(session)?;
+    let count = match state {
+        None => windows.len(),
+        Some(wanted) => StateDetector::new()
+            .detect(&windows)
+            .values()
+            .filter(|s| s.name() == wanted)
+            .count(),
+    };
+    println!("{count}");
+    Ok(())
+}
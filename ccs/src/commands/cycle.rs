@@ -0,0 +1,48 @@
+use crate::colors::{self, Ansi};
+use crate::tmux;
+
+// ── Helpers ──
+
+/// Position after stepping one window forward or back from `current`, wrapping.
+fn step(len: usize, current: usize, forward: bool) -> usize {
+    if forward {
+        (current + 1) % len
+    } else {
+        (current + len - 1) % len
+    }
+}
+
+// ── Public API ──
+
+/// Select the next (or previous) window after the active one.
+pub fn run(session: &str, forward: bool) -> Result<(), String> {
+    let Ansi { overlay, reset, .. } = &colors::ansi();
+    if !tmux::has_session(session) {
+        println!("{overlay}No active ccs session.{reset}");
+        return Err(String::new());
+    }
+
+    let windows = tmux::list_windows(session)?;
+    if windows.len() < 2 {
+        println!("{overlay}Only one session \u{2014} nothing to switch to.{reset}");
+        return Ok(());
+    }
+
+    let current = windows.iter().position(|w| w.is_active).unwrap_or(0);
+    let target = &windows[step(windows.len(), current, forward)];
+    tmux::select_window(&target.window_id)
+}
+
+// ── Tests ──
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_wraps_both_ways() {
+        assert_eq!(step(3, 2, true), 0);
+        assert_eq!(step(3, 0, false), 2);
+        assert_eq!(step(3, 1, true), 2);
+    }
+}
@@ -0,0 +1,205 @@
+// ── Setup diagnostics ──
+//
+// Checks the pieces status detection depends on: tmux, the Claude Code hooks,
+// the hook binary path, and the events directory. With --fix, offers to repair
+// each failed check that can be fixed safely.
+
+use std::fs;
+use std::io::{self, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+
+use crate::colors::{self, Ansi};
+use crate::commands::init;
+use crate::config::Config;
+use crate::paths;
+use crate::tmux;
+
+// ── Types ──
+
+enum Fix {
+    InstallHooks,
+    RewriteHookPaths,
+    CreateEventsDir,
+}
+
+struct Check {
+    name: &'static str,
+    ok: bool,
+    detail: String,
+    /// How to repair a failed check. None means it can't be fixed automatically.
+    fix: Option<Fix>,
+}
+
+// ── Helpers ──
+
+fn check_tmux() -> Check {
+    match tmux::version() {
+        Some(version) => Check {
+            name: "tmux",
+            ok: true,
+            detail: version,
+            fix: None,
+        },
+        None => Check {
+            name: "tmux",
+            ok: false,
+            detail: "not installed or not on PATH".to_string(),
+            fix: None,
+        },
+    }
+}
+
+fn check_hooks(settings: &Path) -> Check {
+    let ok = init::hooks_installed(settings);
+    Check {
+        name: "hooks",
+        ok,
+        detail: if ok {
+            format!("installed in {}", settings.display())
+        } else {
+            format!("missing from {}", settings.display())
+        },
+        fix: Some(Fix::InstallHooks),
+    }
+}
+
+fn check_hook_paths(settings: &Path, bin: &str) -> Check {
+    let stale = init::stale_hook_commands(settings, bin);
+    Check {
+        name: "hook path",
+        ok: stale.is_empty(),
+        detail: match stale.first() {
+            None => bin.to_string(),
+            Some(cmd) => format!("{cmd} (expected {bin})"),
+        },
+        fix: Some(Fix::RewriteHookPaths),
+    }
+}
+
+fn check_events_dir(dir: &Path) -> Check {
+    let ok = fs::metadata(dir).is_ok_and(|m| m.is_dir() && !m.permissions().readonly());
+    Check {
+        name: "events dir",
+        ok,
+        detail: if ok {
+            dir.display().to_string()
+        } else {
+            format!("{} is missing or not writable", dir.display())
+        },
+        fix: Some(Fix::CreateEventsDir),
+    }
+}
+
+fn confirm(prompt: &str) -> bool {
+    print!("{prompt} [Y/n] ");
+    let _ = io::stdout().flush();
+
+    let mut input = String::new();
+    if io::stdin().read_line(&mut input).is_err() {
+        return false;
+    }
+    let answer = input.trim().to_lowercase();
+    answer.is_empty() || answer == "y" || answer == "yes"
+}
+
+fn apply(fix: &Fix, settings: &Path, bin: &str, events: &Path) -> Result<String, String> {
+    match fix {
+        Fix::InstallHooks => {
+            init::install_hooks(settings, &Config::load().hooks)?;
+            Ok("installed hooks".to_string())
+        }
+        Fix::RewriteHookPaths => {
+            let n = init::repair_hook_paths(settings, bin)?;
+            Ok(format!("rewrote {n} hook command(s) to {bin}"))
+        }
+        Fix::CreateEventsDir => {
+            fs::create_dir_all(events).map_err(|e| format!("create events dir: {e}"))?;
+            fs::set_permissions(events, fs::Permissions::from_mode(0o755))
+                .map_err(|e| format!("chmod events dir: {e}"))?;
+            Ok(format!("created {}", events.display()))
+        }
+    }
+}
+
+fn print_check(check: &Check) {
+    let Ansi {
+        green,
+        red,
+        subtext,
+        reset,
+        ..
+    } = &colors::ansi();
+    let (mark, color) = if check.ok {
+        ("\u{2713}", green)
+    } else {
+        ("\u{2717}", red)
+    };
+    println!(
+        "  {color}{mark}{reset} {:<11} {subtext}{}{reset}",
+        check.name, check.detail
+    );
+}
+
+// ── Public API ──
+
+pub fn run(fix: bool) -> Result<(), String> {
+    let Ansi {
+        peach,
+        overlay,
+        green,
+        red,
+        reset,
+        ..
+    } = &colors::ansi();
+    let settings = init::settings_path()?;
+    let bin = init::ccs_bin_path();
+    let events = paths::events_dir()?;
+
+    let checks = [
+        check_tmux(),
+        check_hooks(&settings),
+        check_hook_paths(&settings, &bin),
+        check_events_dir(&events),
+    ];
+
+    for check in &checks {
+        print_check(check);
+    }
+
+    let failed: Vec<&Check> = checks.iter().filter(|c| !c.ok).collect();
+    if failed.is_empty() {
+        return Ok(());
+    }
+
+    if !fix {
+        println!("\nRun {peach}ccs doctor --fix{reset} to repair.");
+        return Err(String::new());
+    }
+
+    println!();
+    let mut unresolved = 0;
+    for check in failed {
+        let Some(f) = &check.fix else {
+            println!("{overlay}Can't fix {} automatically.{reset}", check.name);
+            unresolved += 1;
+            continue;
+        };
+        if !confirm(&format!("Fix {}?", check.name)) {
+            unresolved += 1;
+            continue;
+        }
+        match apply(f, &settings, &bin, &events) {
+            Ok(msg) => println!("  {green}\u{2713}{reset} {msg}"),
+            Err(e) => {
+                eprintln!("  {red}\u{2717}{reset} {e}");
+                unresolved += 1;
+            }
+        }
+    }
+
+    if unresolved > 0 {
+        return Err(String::new());
+    }
+    Ok(())
+}
@@ -0,0 +1,181 @@
+// ── Event log viewer ──
+//
+// Prints the events `ccs hook` recorded, for debugging why the sidebar shows a
+// given state. With no session, every event file is merged by timestamp and each
+// line is tagged with a short session id. --follow keeps polling like `tail -f`.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::colors::{self, Ansi};
+use crate::paths;
+use crate::sidebar::state::EventEntry;
+
+// ── Constants ──
+
+const FOLLOW_INTERVAL: Duration = Duration::from_millis(250);
+
+// ── Helpers ──
+
+/// Format a unix timestamp as `HH:MM:SS` (UTC).
+fn clock(ts: u64) -> String {
+    let secs = ts % 86_400;
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+fn session_of(path: &Path) -> String {
+    path.file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn format_event(event: &EventEntry, session: Option<&str>) -> String {
+    let Ansi {
+        peach,
+        overlay,
+        surface,
+        subtext,
+        reset,
+        ..
+    } = &colors::ansi();
+    let tag = match session {
+        Some(s) => {
+            let short: String = s.chars().take(8).collect();
+            format!("  {surface}{short}{reset}")
+        }
+        None => String::new(),
+    };
+    format!(
+        "{overlay}{}{reset}  {peach}{:<8}{reset}  {subtext}{}{reset}{tag}",
+        clock(event.ts),
+        event.state,
+        event.cwd
+    )
+}
+
+fn parse_lines(content: &str) -> Vec<EventEntry> {
+    content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+fn event_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("jsonl"))
+        .collect()
+}
+
+/// Read everything appended to `path` since `offset`, advancing it.
+/// A file that shrank (trimmed by the hook) is picked up from its new end.
+fn read_new(path: &Path, offset: &mut u64) -> String {
+    let Ok(mut file) = fs::File::open(path) else {
+        return String::new();
+    };
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    if len < *offset {
+        *offset = len;
+    }
+    if file.seek(SeekFrom::Start(*offset)).is_err() {
+        return String::new();
+    }
+    let mut buf = String::new();
+    let _ = file.read_to_string(&mut buf);
+    // Only consume complete lines; a partial trailing line is read next time
+    let complete = buf.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    *offset += complete as u64;
+    buf.truncate(complete);
+    buf
+}
+
+// ── Public API ──
+
+pub fn run(session: Option<&str>, follow: bool) -> Result<(), String> {
+    let dir = paths::events_dir()?;
+
+    let files = match session {
+        Some(id) => {
+            let path = dir.join(format!("{id}.jsonl"));
+            if !path.exists() {
+                return Err(format!("No events for session '{id}' in {}", dir.display()));
+            }
+            vec![path]
+        }
+        None => event_files(&dir),
+    };
+    let tagged = session.is_none();
+
+    let mut offsets: HashMap<PathBuf, u64> = HashMap::new();
+    let mut backlog: Vec<(EventEntry, String)> = Vec::new();
+    for path in &files {
+        let mut offset = 0;
+        let name = session_of(path);
+        for event in parse_lines(&read_new(path, &mut offset)) {
+            backlog.push((event, name.clone()));
+        }
+        offsets.insert(path.clone(), offset);
+    }
+    if tagged {
+        backlog.sort_by_key(|(event, _)| event.ts);
+    }
+    for (event, name) in &backlog {
+        println!("{}", format_event(event, tagged.then_some(name.as_str())));
+    }
+
+    if !follow {
+        return Ok(());
+    }
+
+    loop {
+        std::thread::sleep(FOLLOW_INTERVAL);
+        if tagged {
+            // Pick up sessions started after we began following
+            for path in event_files(&dir) {
+                offsets.entry(path).or_insert(0);
+            }
+        }
+        for (path, offset) in offsets.iter_mut() {
+            let name = session_of(path);
+            for event in parse_lines(&read_new(path, offset)) {
+                println!("{}", format_event(&event, tagged.then_some(name.as_str())));
+            }
+        }
+    }
+}
+
+// ── Tests ──
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_clock() {
+        assert_eq!(clock(0), "00:00:00");
+        assert_eq!(clock(86_400 + 3 * 3600 + 25 * 60 + 7), "03:25:07");
+    }
+
+    #[test]
+    fn test_read_new_only_complete_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("s.jsonl");
+        let mut f = fs::File::create(&path).unwrap();
+        write!(f, "{{\"a\":1}}\n{{\"b\"").unwrap();
+
+        let mut offset = 0;
+        assert_eq!(read_new(&path, &mut offset), "{\"a\":1}\n");
+
+        writeln!(f, ":2}}").unwrap();
+        assert_eq!(read_new(&path, &mut offset), "{\"b\":2}\n");
+        assert_eq!(read_new(&path, &mut offset), "");
+    }
+}
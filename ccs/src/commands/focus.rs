@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+
+use crate::colors::{self, Ansi};
+use crate::sidebar::state::{StateDetector, WindowState};
+use crate::tmux::{self, WindowInfo};
+
+// ── Helpers ──
+
+/// First window that's Asking, else the first that's Idle.
+fn pick<'a>(
+    windows: &'a [WindowInfo],
+    states: &HashMap<u32, WindowState>,
+) -> Option<&'a WindowInfo> {
+    let first_in = |wanted: WindowState| {
+        windows
+            .iter()
+            .find(|w| states.get(&w.index) == Some(&wanted))
+    };
+    first_in(WindowState::Asking).or_else(|| first_in(WindowState::Idle))
+}
+
+// ── Public API ──
+
+/// Jump to the first session waiting on the user.
+pub fn run(session: &str) -> Result<(), String> {
+    let Ansi {
+        peach,
+        overlay,
+        reset,
+        ..
+    } = &colors::ansi();
+    if !tmux::has_session(session) {
+        println!("{overlay}No active ccs session.{reset}");
+        return Err(String::new());
+    }
+
+    let windows = tmux::list_windows(session)?;
+    let states = StateDetector::new().detect(&windows);
+    let Some(win) = pick(&windows, &states) else {
+        println!("{overlay}No session needs attention.{reset}");
+        return Ok(());
+    };
+
+    tmux::select_window(&win.window_id)?;
+    println!("Focused: {peach}{}{reset}", win.name);
+    Ok(())
+}
+
+// ── Tests ──
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tmux::test_window as window;
+
+    #[test]
+    fn test_pick_prefers_asking_over_idle() {
+        let windows = [window(0, "a"), window(1, "b"), window(2, "c")];
+        let mut states = HashMap::new();
+        states.insert(0, WindowState::Idle);
+        states.insert(1, WindowState::Working);
+        states.insert(2, WindowState::Asking);
+        assert_eq!(pick(&windows, &states).unwrap().name, "c");
+
+        states.insert(2, WindowState::Done);
+        assert_eq!(pick(&windows, &states).unwrap().name, "a");
+
+        states.insert(0, WindowState::Fresh);
+        assert!(pick(&windows, &states).is_none());
+    }
+}
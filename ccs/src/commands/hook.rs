@@ -1,21 +1,30 @@
 // ── Claude Code hook handler ──
 //
 // Called by Claude Code hooks to write CCS state events.
-// Reads JSON from stdin, determines state, appends to ~/.ccs/events/{session_id}.jsonl.
+// Reads JSON from stdin, determines state, appends to {events_dir}/{session_id}.jsonl.
 //
 // Hook → state mapping:
 //   UserPromptSubmit           → working
 //   PreToolUse(AskUserQuestion)  → asking
-//   PostToolUse(AskUserQuestion) → working
-//   Stop                       → idle
+//   PostToolUse(AskUserQuestion) → ask-done (shown as working)
+//   Stop                       → idle, or asking/working if the transcript
+//                                ends on a tool_use that never got a result
+//
+// Every event also records the user's latest prompt as a `title`, taken from
+// the UserPromptSubmit payload or recovered from the transcript tail.
 
+use std::collections::HashMap;
 use std::fs::{self, OpenOptions};
-use std::io::{self, Read, Write};
-use std::path::PathBuf;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::Path;
 
 use serde::Deserialize;
+use serde_json::Value;
 
 use crate::cli::HookEvent;
+use crate::config::Config;
+use crate::paths;
+use crate::sidebar::state::EventEntry;
 
 // ── Types ──
 
@@ -23,21 +32,65 @@ use crate::cli::HookEvent;
 struct HookInput {
     session_id: String,
     cwd: String,
+    #[serde(default)]
+    transcript_path: String,
+    /// The submitted text — only present on UserPromptSubmit.
+    #[serde(default)]
+    prompt: String,
 }
 
+// ── Constants ──
+
+/// How much of the transcript tail to scan on Stop. The final assistant turn
+/// and its tool results comfortably fit; older history doesn't matter.
+const TRANSCRIPT_TAIL_BYTES: u64 = 64 * 1024;
+
+/// Events kept when an event file is trimmed. The sidebar only reads the last one;
+/// the rest is history for debugging.
+const KEEP_EVENTS_ON_TRIM: usize = 200;
+
+/// Longest title written to an event, in characters. The sidebar truncates
+/// further to fit; this just keeps event lines small.
+const TITLE_MAX_CHARS: usize = 80;
+
 // ── Helpers ──
 
-fn events_dir() -> PathBuf {
-    let home = std::env::var("HOME").unwrap_or_default();
-    PathBuf::from(home).join(".ccs").join("events")
+/// Rewrite an event file keeping only its last `keep` lines.
+/// Written to a temp file and renamed so a concurrent reader never sees it half-done.
+fn trim_event_file(path: &Path, keep: usize) -> Result<(), String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("read event file: {e}"))?;
+    let lines: Vec<&str> = content.lines().collect();
+    let kept = &lines[lines.len().saturating_sub(keep)..];
+
+    let tmp = path.with_extension(format!("jsonl.{}", std::process::id()));
+    let mut output = kept.join("\n");
+    output.push('\n');
+    fs::write(&tmp, output)
+        .and_then(|()| fs::rename(&tmp, path))
+        .map_err(|e| {
+            let _ = fs::remove_file(&tmp);
+            format!("trim event file: {e}")
+        })
 }
 
-/// Append a state event to the session's event file.
-fn write_event(session_id: &str, cwd: &str, pane_id: &str, state: &str) -> Result<(), String> {
-    let dir = events_dir();
-    fs::create_dir_all(&dir).map_err(|e| format!("create events dir: {e}"))?;
+/// Append a state event to the session's event file in `dir`.
+/// Once the file grows past `max_bytes` it's trimmed to its most recent events first.
+fn write_event(
+    dir: &Path,
+    session_id: &str,
+    cwd: &str,
+    pane_id: &str,
+    state: &str,
+    title: &str,
+    max_bytes: u64,
+) -> Result<(), String> {
+    fs::create_dir_all(dir).map_err(|e| format!("create events dir: {e}"))?;
 
     let path = dir.join(format!("{session_id}.jsonl"));
+    if fs::metadata(&path).is_ok_and(|m| m.len() > max_bytes) {
+        trim_event_file(&path, KEEP_EVENTS_ON_TRIM)?;
+    }
+
     let mut file = OpenOptions::new()
         .create(true)
         .append(true)
@@ -49,12 +102,115 @@ fn write_event(session_id: &str, cwd: &str, pane_id: &str, state: &str) -> Resul
         .unwrap_or_default()
         .as_secs();
 
-    let line = format!(r#"{{"state":"{state}","cwd":"{cwd}","pane_id":"{pane_id}","ts":{ts}}}"#);
+    let event = EventEntry {
+        state: state.to_string(),
+        cwd: cwd.to_string(),
+        pane_id: pane_id.to_string(),
+        ts,
+        title: title.to_string(),
+    };
+    let line = serde_json::to_string(&event).map_err(|e| format!("encode event: {e}"))?;
     writeln!(file, "{line}").map_err(|e| format!("write event: {e}"))?;
 
     Ok(())
 }
 
+/// Read up to the last `max` bytes of a file. Returns an empty string on error.
+fn read_tail(path: &Path, max: u64) -> String {
+    let Ok(mut file) = fs::File::open(path) else {
+        return String::new();
+    };
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    if file.seek(SeekFrom::Start(len.saturating_sub(max))).is_err() {
+        return String::new();
+    }
+    let mut buf = Vec::new();
+    let _ = file.read_to_end(&mut buf);
+    String::from_utf8_lossy(&buf).into_owned()
+}
+
+/// Classify a Stop event from the tail of the session transcript.
+///
+/// Walks the JSONL entries in order, tracking `tool_use` blocks from assistant
+/// messages until a matching `tool_result` arrives. Anything still pending at
+/// the end means Claude's last turn is waiting on a tool: `asking` for
+/// AskUserQuestion, `working` for anything else. Otherwise `idle`.
+///
+/// Unparseable lines (including a partial first line from the tail read) are skipped.
+fn determine_stop_state(transcript_tail: &str) -> &'static str {
+    let mut pending: HashMap<String, String> = HashMap::new();
+
+    for line in transcript_tail.lines() {
+        let Ok(entry) = serde_json::from_str::<Value>(line) else {
+            continue;
+        };
+        let Some(blocks) = entry["message"]["content"].as_array() else {
+            continue;
+        };
+        for block in blocks {
+            match block["type"].as_str() {
+                Some("tool_use") if entry["type"] == "assistant" => {
+                    let id = block["id"].as_str().unwrap_or_default();
+                    let name = block["name"].as_str().unwrap_or_default();
+                    pending.insert(id.to_string(), name.to_string());
+                }
+                Some("tool_result") => {
+                    let id = block["tool_use_id"].as_str().unwrap_or_default();
+                    pending.remove(id);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    if pending.values().any(|name| name == "AskUserQuestion") {
+        "asking"
+    } else if !pending.is_empty() {
+        "working"
+    } else {
+        "idle"
+    }
+}
+
+/// Text of the last real user prompt in a transcript tail.
+///
+/// Tool results come back as `user` entries too; those (and meta entries such as
+/// injected command output) are skipped so the title stays on what was typed.
+fn last_user_prompt(transcript_tail: &str) -> Option<String> {
+    let mut last = None;
+    for line in transcript_tail.lines() {
+        let Ok(entry) = serde_json::from_str::<Value>(line) else {
+            continue;
+        };
+        if entry["type"] != "user" || entry["isMeta"] == true {
+            continue;
+        }
+        let text = match &entry["message"]["content"] {
+            Value::String(text) => text.clone(),
+            Value::Array(blocks) => blocks
+                .iter()
+                .filter(|b| b["type"] == "text")
+                .filter_map(|b| b["text"].as_str())
+                .collect::<Vec<_>>()
+                .join(" "),
+            _ => continue,
+        };
+        if !text.trim().is_empty() {
+            last = Some(text);
+        }
+    }
+    last
+}
+
+/// First non-blank line of a prompt, capped at `TITLE_MAX_CHARS`.
+fn prompt_title(prompt: &str) -> String {
+    let line = prompt.lines().map(str::trim).find(|l| !l.is_empty());
+    line.unwrap_or_default()
+        .chars()
+        .take(TITLE_MAX_CHARS)
+        .collect()
+}
+
 // ── Public API ──
 
 pub fn run(event: HookEvent) -> Result<(), String> {
@@ -66,9 +222,22 @@ pub fn run(event: HookEvent) -> Result<(), String> {
     let hook: HookInput =
         serde_json::from_str(&input).map_err(|e| format!("parse hook input: {e}"))?;
 
+    // UserPromptSubmit fires before the prompt reaches the transcript, but
+    // carries the text itself. Other events recover it from the tail.
+    let tail = match event {
+        HookEvent::UserPrompt if !hook.prompt.is_empty() => String::new(),
+        _ => read_tail(Path::new(&hook.transcript_path), TRANSCRIPT_TAIL_BYTES),
+    };
+    let title = if hook.prompt.is_empty() {
+        last_user_prompt(&tail).map_or_else(String::new, |p| prompt_title(&p))
+    } else {
+        prompt_title(&hook.prompt)
+    };
+
     let state = match event {
-        HookEvent::UserPrompt | HookEvent::AskDone => "working",
-        HookEvent::Stop => "idle",
+        HookEvent::UserPrompt => "working",
+        HookEvent::AskDone => "ask-done",
+        HookEvent::Stop => determine_stop_state(&tail),
         HookEvent::Ask => "asking",
     };
 
@@ -76,7 +245,15 @@ pub fn run(event: HookEvent) -> Result<(), String> {
     // This lets the sidebar distinguish sessions even when they share a cwd.
     let pane_id = std::env::var("TMUX_PANE").unwrap_or_default();
 
-    write_event(&hook.session_id, &hook.cwd, &pane_id, state)
+    write_event(
+        &paths::events_dir()?,
+        &hook.session_id,
+        &hook.cwd,
+        &pane_id,
+        state,
+        &title,
+        Config::load().events.max_bytes,
+    )
 }
 
 // ── Tests ──
@@ -84,25 +261,166 @@ pub fn run(event: HookEvent) -> Result<(), String> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::io::Write;
 
     #[test]
     fn test_write_event_creates_file() {
         let dir = tempfile::tempdir().unwrap();
         let events = dir.path().join("events");
 
-        fs::create_dir_all(&events).unwrap();
-        let path = events.join("test-session.jsonl");
-
-        let mut file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&path)
-            .unwrap();
-        writeln!(file, r#"{{"state":"working","cwd":"/tmp","ts":1234}}"#).unwrap();
+        write_event(&events, "test-session", "/tmp", "%1", "working", "", 1024).unwrap();
 
+        let path = events.join("test-session.jsonl");
         let content = fs::read_to_string(&path).unwrap();
         assert!(content.contains(r#""state":"working""#));
         assert!(content.contains(r#""cwd":"/tmp""#));
+        assert!(content.contains(r#""pane_id":"%1""#));
+    }
+
+    #[test]
+    fn test_write_event_appends() {
+        let dir = tempfile::tempdir().unwrap();
+
+        write_event(dir.path(), "s", "/tmp", "%1", "working", "", 1024).unwrap();
+        write_event(dir.path(), "s", "/tmp", "%1", "idle", "", 1024).unwrap();
+
+        let content = fs::read_to_string(dir.path().join("s.jsonl")).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].contains(r#""state":"idle""#));
+    }
+
+    #[test]
+    fn test_write_event_trims_large_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("s.jsonl");
+        let old: String = (0..KEEP_EVENTS_ON_TRIM + 50)
+            .map(|i| format!("{{\"state\":\"working\",\"cwd\":\"/tmp\",\"ts\":{i}}}\n"))
+            .collect();
+        fs::write(&path, &old).unwrap();
+
+        write_event(dir.path(), "s", "/tmp", "%1", "idle", "", 1024).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), KEEP_EVENTS_ON_TRIM + 1);
+        assert!(lines[0].contains(r#""ts":50"#));
+        assert!(lines.last().unwrap().contains(r#""state":"idle""#));
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_write_event_escapes_title() {
+        let dir = tempfile::tempdir().unwrap();
+
+        write_event(dir.path(), "s", "/tmp", "%1", "working", r#"fix "x""#, 1024).unwrap();
+
+        let content = fs::read_to_string(dir.path().join("s.jsonl")).unwrap();
+        let event: Value = serde_json::from_str(content.trim()).unwrap();
+        assert_eq!(event["title"], r#"fix "x""#);
+        assert_eq!(event["state"], "working");
+    }
+
+    #[test]
+    fn test_write_event_round_trips_cwd() {
+        let dir = tempfile::tempdir().unwrap();
+        let cwd = r#"/tmp/say "hi"\back\slash"#;
+
+        // A quote in the session id only ends up in the file name
+        write_event(dir.path(), r#"s"1"#, cwd, "%1", "idle", "", 1024).unwrap();
+
+        let content = fs::read_to_string(dir.path().join(r#"s"1.jsonl"#)).unwrap();
+        let event: EventEntry = serde_json::from_str(content.trim()).unwrap();
+        assert_eq!(event.cwd, cwd);
+        assert_eq!(event.pane_id, "%1");
+        assert_eq!(event.state, "idle");
+        assert!(!content.contains("title"));
+    }
+
+    #[test]
+    fn test_last_user_prompt_skips_tool_results() {
+        let tail = concat!(
+            r#"{"type":"user","message":{"content":"fix the bug"}}"#,
+            "\n",
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","id":"t1","name":"Bash","input":{}}]}}"#,
+            "\n",
+            r#"{"type":"user","message":{"content":[{"type":"tool_result","tool_use_id":"t1","content":"ok"}]}}"#,
+        );
+        assert_eq!(last_user_prompt(tail).as_deref(), Some("fix the bug"));
+    }
+
+    #[test]
+    fn test_last_user_prompt_reads_text_blocks() {
+        let tail = concat!(
+            r#"{"type":"user","message":{"content":"first"}}"#,
+            "\n",
+            r#"{"type":"user","message":{"content":[{"type":"text","text":"second"}]}}"#,
+            "\n",
+            r#"{"type":"user","isMeta":true,"message":{"content":"caveat"}}"#,
+        );
+        assert_eq!(last_user_prompt(tail).as_deref(), Some("second"));
+        assert_eq!(last_user_prompt(""), None);
+    }
+
+    #[test]
+    fn test_prompt_title_first_line_capped() {
+        assert_eq!(prompt_title("\n  add tests  \nmore detail"), "add tests");
+        assert_eq!(prompt_title(&"x".repeat(200)).len(), TITLE_MAX_CHARS);
+        assert_eq!(prompt_title("   "), "");
+    }
+
+    #[test]
+    fn test_stop_state_idle_after_text() {
+        let tail = concat!(
+            r#"{"type":"user","message":{"role":"user","content":"fix the bug"}}"#,
+            "\n",
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Done."}]}}"#,
+        );
+        assert_eq!(determine_stop_state(tail), "idle");
+    }
+
+    #[test]
+    fn test_stop_state_answered_tool_use_is_idle() {
+        let tail = concat!(
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","id":"t1","name":"Bash","input":{}}]}}"#,
+            "\n",
+            r#"{"type":"user","message":{"content":[{"type":"tool_result","tool_use_id":"t1","content":"ok"}]}}"#,
+            "\n",
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"All set."}]}}"#,
+        );
+        assert_eq!(determine_stop_state(tail), "idle");
+    }
+
+    #[test]
+    fn test_stop_state_pending_ask_compact() {
+        let tail = r#"{"type":"assistant","message":{"content":[{"type":"tool_use","id":"t1","name":"AskUserQuestion","input":{}}]}}"#;
+        assert_eq!(determine_stop_state(tail), "asking");
+    }
+
+    #[test]
+    fn test_stop_state_pending_ask_spaced() {
+        let tail = r#"{"type": "assistant", "message": {"content": [{"type": "tool_use", "id": "t1", "name": "AskUserQuestion", "input": {}}]}}"#;
+        assert_eq!(determine_stop_state(tail), "asking");
+    }
+
+    #[test]
+    fn test_stop_state_trailing_non_ask_tool_use() {
+        let tail = concat!(
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","id":"t1","name":"AskUserQuestion","input":{}}]}}"#,
+            "\n",
+            r#"{"type":"user","message":{"content":[{"type":"tool_result","tool_use_id":"t1","content":"yes"}]}}"#,
+            "\n",
+            r#"{"type":"assistant","message":{"content":[{"type":"tool_use","id":"t2","name":"Bash","input":{"command":"cargo build"}}]}}"#,
+        );
+        assert_eq!(determine_stop_state(tail), "working");
+    }
+
+    #[test]
+    fn test_stop_state_skips_partial_first_line() {
+        let tail = concat!(
+            r#"ol_use","id":"t0","name":"AskUserQuestion"}]}}"#,
+            "\n",
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Done."}]}}"#,
+        );
+        assert_eq!(determine_stop_state(tail), "idle");
     }
 }
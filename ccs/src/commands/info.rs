@@ -0,0 +1,60 @@
+use crate::colors::{self, Ansi};
+use crate::paths;
+use crate::sidebar::state::{self, StateDetector};
+use crate::tmux;
+
+/// Print everything ccs knows about one session tab. Read-only.
+pub fn run(session: &str, name: &str) -> Result<(), String> {
+    let Ansi {
+        peach,
+        overlay,
+        subtext,
+        bold,
+        reset,
+        ..
+    } = &colors::ansi();
+    if !tmux::has_session(session) {
+        println!("{overlay}No active ccs session.{reset}");
+        return Err(String::new());
+    }
+
+    let windows = tmux::list_windows(session)?;
+    let Some(win) = windows.iter().find(|w| w.name == name) else {
+        return Err(format!("No session named '{peach}{name}{reset}'."));
+    };
+
+    let pid = tmux::pane_pid(&win.pane_id)
+        .map(|pid| pid.to_string())
+        .unwrap_or_else(|_| "?".to_string());
+    let detected = StateDetector::new().detect(std::slice::from_ref(win));
+    let recorded = state::load_latest_events(&paths::events_dir()?)
+        .into_iter()
+        .find(|e| e.pane_id == win.pane_id)
+        .map(|e| e.state)
+        .unwrap_or_else(|| "none".to_string());
+
+    let rows = [
+        ("index", win.index.to_string()),
+        ("window", win.window_id.clone()),
+        ("pane", format!("{} (pid {pid})", win.pane_id)),
+        (
+            "command",
+            if win.dead {
+                format!("{} (pane dead)", win.command)
+            } else {
+                win.command.clone()
+            },
+        ),
+        ("cwd", paths::tilde(&win.pane_path)),
+        (
+            "state",
+            format!("{:?} (recorded: {recorded})", detected[&win.index]),
+        ),
+    ];
+
+    println!("{peach}{bold}{}{reset}", win.name);
+    for (key, value) in rows {
+        println!("  {overlay}{key:<8}{reset} {subtext}{value}{reset}");
+    }
+    Ok(())
+}
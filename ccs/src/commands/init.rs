@@ -1,28 +1,56 @@
 // ── Hook installation for Claude Code ──
 //
-// Adds CCS hook entries to ~/.claude/settings.json so Claude Code
-// calls `ccs hook user-prompt` and `ccs hook stop` on session events.
+// Adds CCS hook entries to ~/.claude/settings.json (or a project's
+// .claude/settings.json) so Claude Code calls `ccs hook user-prompt`
+// and `ccs hook stop` on session events.
 
 use std::fs;
+use std::io::Write;
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
 use std::path::{Path, PathBuf};
 
 use serde_json::Value;
 
+use crate::config::HooksConfig;
+use crate::paths;
+
+// ── Constants ──
+
+/// Each entry: (hook_type, matcher, ccs_command)
+const HOOK_ENTRIES: &[(&str, &str, &str)] = &[
+    ("UserPromptSubmit", "*", "hook user-prompt"),
+    ("Stop", "*", "hook stop"),
+    ("PreToolUse", "AskUserQuestion", "hook ask"),
+    ("PostToolUse", "AskUserQuestion", "hook ask-done"),
+];
+
 // ── Helpers ──
 
-fn settings_path() -> PathBuf {
-    let home = std::env::var("HOME").unwrap_or_default();
-    PathBuf::from(home).join(".claude").join("settings.json")
+pub fn settings_path() -> Result<PathBuf, String> {
+    Ok(paths::home_dir()?.join(".claude").join("settings.json"))
 }
 
-fn ccs_bin_path() -> String {
-    if let Ok(exe) = std::env::current_exe() {
-        if let Ok(canonical) = fs::canonicalize(exe) {
-            return canonical.to_string_lossy().to_string();
-        }
+/// Project-local settings file, checked in alongside the project.
+fn project_settings_path(dir: &Path) -> PathBuf {
+    dir.join(".claude").join("settings.json")
+}
+
+/// Collapse the home directory to `~` for display.
+fn display_path(path: &Path) -> String {
+    paths::tilde(&path.to_string_lossy())
+}
+
+pub fn ccs_bin_path() -> String {
+    if let Ok(exe) = std::env::current_exe()
+        && let Ok(canonical) = fs::canonicalize(exe)
+    {
+        return canonical.to_string_lossy().to_string();
+    }
+    // Expected install location, else whatever `ccs` is on PATH
+    match paths::home_dir() {
+        Ok(home) => home.join(".local/bin/ccs").to_string_lossy().to_string(),
+        Err(_) => "ccs".to_string(),
     }
-    let home = std::env::var("HOME").unwrap_or_default();
-    format!("{home}/.local/bin/ccs")
 }
 
 /// Check if CCS hooks are already installed in settings.json.
@@ -37,38 +65,37 @@ pub fn hooks_installed(path: &Path) -> bool {
 
 /// Install CCS hooks into settings.json.
 /// Appends to existing hook arrays — does not overwrite.
-pub fn install_hooks(path: &Path) -> Result<(), String> {
-    install_hooks_with_bin(path, &ccs_bin_path())
+pub fn install_hooks(path: &Path, opts: &HooksConfig) -> Result<(), String> {
+    install_hooks_with_bin(path, &ccs_bin_path(), opts)
 }
 
-/// Check if a hook array already contains an entry whose command includes `needle`.
-fn has_hook_command(arr: &[Value], needle: &str) -> bool {
-    arr.iter().any(|entry| {
-        entry["hooks"]
-            .as_array()
-            .map(|hooks| {
-                hooks.iter().any(|h| {
-                    h["command"]
-                        .as_str()
-                        .map(|c| c.contains(needle))
-                        .unwrap_or(false)
-                })
-            })
-            .unwrap_or(false)
-    })
+/// Find the hook object in a hook array whose command includes `needle`.
+fn find_hook_command<'a>(arr: &'a mut [Value], needle: &str) -> Option<&'a mut Value> {
+    arr.iter_mut()
+        .filter_map(|entry| entry.get_mut("hooks").and_then(Value::as_array_mut))
+        .flat_map(|hooks| hooks.iter_mut())
+        .find(|h| {
+            h["command"]
+                .as_str()
+                .map(|c| c.contains(needle))
+                .unwrap_or(false)
+        })
 }
 
-fn install_hooks_with_bin(path: &Path, bin: &str) -> Result<(), String> {
+/// Read settings.json (or start from `{}`) and merge CCS hook entries into it.
+/// Appends to existing hook arrays — does not overwrite. CCS entries left behind
+/// by a previous binary location are repointed at `bin` rather than duplicated,
+/// and existing entries pick up the current async/timeout values.
+fn merge_hooks(path: &Path, bin: &str, opts: &HooksConfig) -> Result<Value, String> {
     let mut settings: Value = if path.exists() {
         let content = fs::read_to_string(path).map_err(|e| format!("read settings: {e}"))?;
         serde_json::from_str(&content).map_err(|e| format!("parse settings: {e}"))?
     } else {
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent).map_err(|e| format!("create settings dir: {e}"))?;
-        }
         serde_json::json!({})
     };
 
+    rewrite_stale_commands(&mut settings, bin);
+
     let hooks = settings
         .as_object_mut()
         .ok_or("settings.json is not an object")?
@@ -77,53 +104,234 @@ fn install_hooks_with_bin(path: &Path, bin: &str) -> Result<(), String> {
 
     let hooks_obj = hooks.as_object_mut().ok_or("hooks is not an object")?;
 
-    // Each entry: (hook_type, matcher, ccs_command)
-    let entries: &[(&str, &str, &str)] = &[
-        ("UserPromptSubmit", "*", "hook user-prompt"),
-        ("Stop", "*", "hook stop"),
-        ("PreToolUse", "AskUserQuestion", "hook ask"),
-        ("PostToolUse", "AskUserQuestion", "hook ask-done"),
-    ];
-
-    for &(hook_type, matcher, cmd) in entries {
+    for &(hook_type, matcher, cmd) in HOOK_ENTRIES {
         let arr = hooks_obj
             .entry(hook_type)
             .or_insert_with(|| serde_json::json!([]));
-        let arr = arr.as_array_mut().ok_or(format!("{hook_type} is not an array"))?;
+        let arr = arr
+            .as_array_mut()
+            .ok_or(format!("{hook_type} is not an array"))?;
 
         let full_cmd = format!("{bin} {cmd}");
-        if !has_hook_command(arr, &full_cmd) {
-            arr.push(serde_json::json!({
+        match find_hook_command(arr, &full_cmd) {
+            Some(hook) => {
+                hook["async"] = serde_json::json!(opts.run_async);
+                hook["timeout"] = serde_json::json!(opts.timeout);
+            }
+            None => arr.push(serde_json::json!({
                 "matcher": matcher,
                 "hooks": [{
                     "type": "command",
                     "command": full_cmd,
-                    "async": true,
-                    "timeout": 5
+                    "async": opts.run_async,
+                    "timeout": opts.timeout
                 }]
-            }));
+            })),
         }
     }
 
+    Ok(settings)
+}
+
+/// Write settings.json atomically: write a sibling temp file, then rename it into
+/// place. Claude Code (or another ccs) reading concurrently never sees a partial file,
+/// and a failed write leaves the original untouched. A symlinked settings.json (as
+/// this dotfiles repo installs it) is resolved so the rename replaces the target,
+/// not the link. The original's permissions carry over to the replacement.
+fn write_settings(path: &Path, settings: &Value) -> Result<(), String> {
     let output =
-        serde_json::to_string_pretty(&settings).map_err(|e| format!("serialize settings: {e}"))?;
-    fs::write(path, output).map_err(|e| format!("write settings: {e}"))?;
+        serde_json::to_string_pretty(settings).map_err(|e| format!("serialize settings: {e}"))?;
+
+    let resolved = fs::canonicalize(path).ok();
+    let path = resolved.as_deref().unwrap_or(path);
+
+    let parent = path.parent().unwrap_or(Path::new("."));
+    fs::create_dir_all(parent).map_err(|e| format!("create settings dir: {e}"))?;
+
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let tmp = parent.join(format!(".{file_name}.ccs-{}", std::process::id()));
+    // Created with the original mode, so the contents are never readable
+    // more widely than the file they replace
+    let mode = fs::metadata(path).map_or(0o666, |m| m.permissions().mode() & 0o777);
+    let result = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(mode)
+        .open(&tmp)
+        .and_then(|mut file| file.write_all(output.as_bytes()))
+        .and_then(|()| fs::rename(&tmp, path));
+    if let Err(e) = result {
+        let _ = fs::remove_file(&tmp);
+        return Err(format!("write settings: {e}"));
+    }
 
     Ok(())
 }
 
+fn install_hooks_with_bin(path: &Path, bin: &str, opts: &HooksConfig) -> Result<(), String> {
+    let settings = merge_hooks(path, bin, opts)?;
+    write_settings(path, &settings)
+}
+
+/// Render the settings.json that `install_hooks` would write, without touching the file.
+fn preview_hooks_with_bin(path: &Path, bin: &str, opts: &HooksConfig) -> Result<String, String> {
+    let settings = merge_hooks(path, bin, opts)?;
+    serde_json::to_string_pretty(&settings).map_err(|e| format!("serialize settings: {e}"))
+}
+
+/// Split a CCS hook command into its binary path and `hook ...` suffix.
+/// Returns None for commands that aren't CCS hooks (e.g. `afplay sound.aiff`).
+fn split_ccs_command(command: &str) -> Option<(&str, &str)> {
+    HOOK_ENTRIES.iter().find_map(|&(_, _, suffix)| {
+        let bin = command.strip_suffix(suffix)?.strip_suffix(' ')?;
+        let is_ccs = Path::new(bin).file_name().is_some_and(|name| name == "ccs");
+        is_ccs.then_some((bin, suffix))
+    })
+}
+
+/// Visit every hook command string in a settings object.
+fn for_each_command(settings: &mut Value, mut f: impl FnMut(&mut Value)) {
+    let Some(hooks) = settings.get_mut("hooks").and_then(Value::as_object_mut) else {
+        return;
+    };
+    for arr in hooks.values_mut().filter_map(Value::as_array_mut) {
+        for entry in arr.iter_mut() {
+            let Some(inner) = entry.get_mut("hooks").and_then(Value::as_array_mut) else {
+                continue;
+            };
+            for command in inner.iter_mut().filter_map(|h| h.get_mut("command")) {
+                f(command);
+            }
+        }
+    }
+}
+
+/// CCS hook commands in settings.json whose binary path differs from `bin`.
+pub fn stale_hook_commands(path: &Path, bin: &str) -> Vec<String> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    let Ok(mut settings) = serde_json::from_str::<Value>(&content) else {
+        return Vec::new();
+    };
+
+    let mut stale = Vec::new();
+    for_each_command(&mut settings, |command| {
+        if let Some(cmd) = command.as_str()
+            && let Some((old_bin, _)) = split_ccs_command(cmd)
+            && old_bin != bin
+        {
+            stale.push(cmd.to_string());
+        }
+    });
+    stale
+}
+
+/// Rewrite the binary path of stale CCS hook commands to `bin`, keeping the
+/// `hook ...` suffix and every other field. Hooks that become duplicates of an
+/// existing up-to-date one are dropped. Returns how many commands changed.
+fn rewrite_stale_commands(settings: &mut Value, bin: &str) -> usize {
+    let mut changed = 0;
+    for_each_command(settings, |command| {
+        if let Some(cmd) = command.as_str()
+            && let Some((old_bin, suffix)) = split_ccs_command(cmd)
+            && old_bin != bin
+        {
+            *command = Value::String(format!("{bin} {suffix}"));
+            changed += 1;
+        }
+    });
+
+    if changed > 0 {
+        dedupe_ccs_entries(settings);
+    }
+    changed
+}
+
+/// Drop CCS hooks that repeat an earlier one with the same matcher in the same
+/// hook array, then any entry that loses all its hooks. Other hooks sharing
+/// an entry with a duplicate stay where they are.
+fn dedupe_ccs_entries(settings: &mut Value) {
+    let Some(hooks) = settings.get_mut("hooks").and_then(Value::as_object_mut) else {
+        return;
+    };
+    for arr in hooks.values_mut().filter_map(Value::as_array_mut) {
+        let mut seen: Vec<(Option<String>, String)> = Vec::new();
+        arr.retain_mut(|entry| {
+            let matcher = entry["matcher"].as_str().map(str::to_string);
+            let Some(inner) = entry.get_mut("hooks").and_then(Value::as_array_mut) else {
+                return true;
+            };
+            let before = inner.len();
+            inner.retain(|hook| {
+                let Some(cmd) = hook["command"].as_str() else {
+                    return true;
+                };
+                if split_ccs_command(cmd).is_none() {
+                    return true;
+                }
+                let key = (matcher.clone(), cmd.to_string());
+                if seen.contains(&key) {
+                    return false;
+                }
+                seen.push(key);
+                true
+            });
+            before == 0 || !inner.is_empty()
+        });
+    }
+}
+
+/// Rewrite stale CCS hook binary paths in settings.json to `bin`.
+/// Returns how many commands changed.
+pub fn repair_hook_paths(path: &Path, bin: &str) -> Result<usize, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("read settings: {e}"))?;
+    let mut settings: Value =
+        serde_json::from_str(&content).map_err(|e| format!("parse settings: {e}"))?;
+
+    let changed = rewrite_stale_commands(&mut settings, bin);
+    if changed > 0 {
+        write_settings(path, &settings)?;
+    }
+
+    Ok(changed)
+}
+
 // ── Public API ──
 
-pub fn run() -> Result<(), String> {
-    let path = settings_path();
+/// Install hooks into the user settings, or into `<project>/.claude/settings.json`
+/// when a project directory is given. `force` re-runs the merge even when hooks
+/// are already present, which repairs stale binary paths and fills in missing entries.
+pub fn run(
+    dry_run: bool,
+    force: bool,
+    project: Option<&Path>,
+    opts: &HooksConfig,
+) -> Result<(), String> {
+    let path = match project {
+        Some(dir) => project_settings_path(dir),
+        None => settings_path()?,
+    };
+    let shown = display_path(&path);
+
+    if !force && hooks_installed(&path) {
+        println!("CCS hooks are already installed in {shown}");
+        if !stale_hook_commands(&path, &ccs_bin_path()).is_empty() {
+            println!(
+                "Some hooks point at an old ccs binary. Run `ccs init --force` to repair them."
+            );
+        }
+        return Ok(());
+    }
 
-    if hooks_installed(&path) {
-        println!("CCS hooks are already installed in ~/.claude/settings.json");
+    if dry_run {
+        println!("{}", preview_hooks_with_bin(&path, &ccs_bin_path(), opts)?);
         return Ok(());
     }
 
-    install_hooks(&path)?;
-    println!("Installed CCS hooks in ~/.claude/settings.json");
+    install_hooks(&path, opts)?;
+    println!("Installed CCS hooks in {shown}");
     println!("  UserPromptSubmit              → ccs hook user-prompt");
     println!("  Stop                          → ccs hook stop");
     println!("  PreToolUse(AskUserQuestion)   → ccs hook ask");
@@ -157,8 +365,11 @@ mod tests {
         let dir = tempfile::tempdir().unwrap();
         let path = dir.path().join("settings.json");
         // Old installation — has "ccs hook stop" but not "ccs hook ask"
-        fs::write(&path, r#"{"hooks":{"Stop":[{"hooks":[{"command":"ccs hook stop"}]}]}}"#)
-            .unwrap();
+        fs::write(
+            &path,
+            r#"{"hooks":{"Stop":[{"hooks":[{"command":"ccs hook stop"}]}]}}"#,
+        )
+        .unwrap();
 
         assert!(!hooks_installed(&path));
     }
@@ -182,7 +393,7 @@ mod tests {
         let path = dir.path().join("settings.json");
         fs::write(&path, "{}").unwrap();
 
-        install_hooks_with_bin(&path, "ccs").unwrap();
+        install_hooks_with_bin(&path, "ccs", &HooksConfig::default()).unwrap();
 
         let content = fs::read_to_string(&path).unwrap();
         assert!(content.contains("ccs hook user-prompt"));
@@ -212,7 +423,7 @@ mod tests {
         )
         .unwrap();
 
-        install_hooks_with_bin(&path, "ccs").unwrap();
+        install_hooks_with_bin(&path, "ccs", &HooksConfig::default()).unwrap();
 
         let content = fs::read_to_string(&path).unwrap();
         let parsed: Value = serde_json::from_str(&content).unwrap();
@@ -220,14 +431,18 @@ mod tests {
         // Stop should have 2 entries: original + CCS
         let stop = parsed["hooks"]["Stop"].as_array().unwrap();
         assert_eq!(stop.len(), 2);
-        assert!(stop[0]["hooks"][0]["command"]
-            .as_str()
-            .unwrap()
-            .contains("afplay"));
-        assert!(stop[1]["hooks"][0]["command"]
-            .as_str()
-            .unwrap()
-            .contains("ccs hook stop"));
+        assert!(
+            stop[0]["hooks"][0]["command"]
+                .as_str()
+                .unwrap()
+                .contains("afplay")
+        );
+        assert!(
+            stop[1]["hooks"][0]["command"]
+                .as_str()
+                .unwrap()
+                .contains("ccs hook stop")
+        );
     }
 
     #[test]
@@ -236,8 +451,8 @@ mod tests {
         let path = dir.path().join("settings.json");
         fs::write(&path, "{}").unwrap();
 
-        install_hooks_with_bin(&path, "ccs").unwrap();
-        install_hooks_with_bin(&path, "ccs").unwrap();
+        install_hooks_with_bin(&path, "ccs", &HooksConfig::default()).unwrap();
+        install_hooks_with_bin(&path, "ccs", &HooksConfig::default()).unwrap();
 
         let content = fs::read_to_string(&path).unwrap();
         let parsed: Value = serde_json::from_str(&content).unwrap();
@@ -250,18 +465,207 @@ mod tests {
         assert_eq!(hooks["PostToolUse"].as_array().unwrap().len(), 1);
     }
 
+    #[test]
+    fn test_install_hooks_updates_timeout() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        fs::write(&path, "{}").unwrap();
+
+        install_hooks_with_bin(&path, "ccs", &HooksConfig::default()).unwrap();
+        let slow = HooksConfig {
+            timeout: 30,
+            run_async: false,
+        };
+        install_hooks_with_bin(&path, "ccs", &slow).unwrap();
+
+        let parsed: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let stop = parsed["hooks"]["Stop"].as_array().unwrap();
+        assert_eq!(stop.len(), 1);
+        assert_eq!(stop[0]["hooks"][0]["timeout"].as_u64().unwrap(), 30);
+        assert!(!stop[0]["hooks"][0]["async"].as_bool().unwrap());
+    }
+
     #[test]
     fn test_install_hooks_creates_file() {
         let dir = tempfile::tempdir().unwrap();
         let path = dir.path().join("subdir").join("settings.json");
 
-        install_hooks_with_bin(&path, "ccs").unwrap();
+        install_hooks_with_bin(&path, "ccs", &HooksConfig::default()).unwrap();
 
         assert!(path.exists());
         let content = fs::read_to_string(&path).unwrap();
         assert!(content.contains("ccs hook ask"));
     }
 
+    #[test]
+    fn test_write_settings_leaves_no_temp_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        fs::write(&path, r#"{"old":true}"#).unwrap();
+
+        write_settings(&path, &serde_json::json!({"new": true})).unwrap();
+
+        let parsed: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(parsed, serde_json::json!({"new": true}));
+        let names: Vec<_> = fs::read_dir(dir.path()).unwrap().flatten().collect();
+        assert_eq!(names.len(), 1);
+    }
+
+    #[test]
+    fn test_write_settings_keeps_permissions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        fs::write(&path, "{}").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+
+        write_settings(&path, &serde_json::json!({"new": true})).unwrap();
+
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn test_write_settings_keeps_symlink() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("dotfiles-settings.json");
+        let link = dir.path().join("settings.json");
+        fs::write(&target, "{}").unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        write_settings(&link, &serde_json::json!({"new": true})).unwrap();
+
+        assert!(
+            fs::symlink_metadata(&link)
+                .unwrap()
+                .file_type()
+                .is_symlink()
+        );
+        assert!(fs::read_to_string(&target).unwrap().contains("new"));
+    }
+
+    #[test]
+    fn test_preview_hooks_does_not_write() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        fs::write(&path, r#"{"model":"opus"}"#).unwrap();
+
+        let preview = preview_hooks_with_bin(&path, "ccs", &HooksConfig::default()).unwrap();
+
+        let parsed: Value = serde_json::from_str(&preview).unwrap();
+        assert_eq!(parsed["model"].as_str().unwrap(), "opus");
+        assert_eq!(parsed["hooks"]["Stop"].as_array().unwrap().len(), 1);
+        assert_eq!(fs::read_to_string(&path).unwrap(), r#"{"model":"opus"}"#);
+    }
+
+    #[test]
+    fn test_preview_hooks_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("subdir").join("settings.json");
+
+        let preview = preview_hooks_with_bin(&path, "ccs", &HooksConfig::default()).unwrap();
+
+        assert!(preview.contains("ccs hook ask"));
+        assert!(!path.parent().unwrap().exists());
+    }
+
+    #[test]
+    fn test_install_hooks_project_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = project_settings_path(dir.path());
+        assert_eq!(path, dir.path().join(".claude/settings.json"));
+
+        install_hooks_with_bin(&path, "ccs", &HooksConfig::default()).unwrap();
+        assert!(hooks_installed(&path));
+    }
+
+    #[test]
+    fn test_stale_hook_commands() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        fs::write(
+            &path,
+            r#"{"hooks":{"Stop":[{"hooks":[{"command":"/old/bin/ccs hook stop"},{"command":"afplay done.aiff"}]}],"PreToolUse":[{"hooks":[{"command":"/new/bin/ccs hook ask"}]}]}}"#,
+        )
+        .unwrap();
+
+        let stale = stale_hook_commands(&path, "/new/bin/ccs");
+        assert_eq!(stale, vec!["/old/bin/ccs hook stop".to_string()]);
+    }
+
+    #[test]
+    fn test_split_ccs_command() {
+        assert_eq!(
+            split_ccs_command("/usr/local/bin/ccs hook ask-done"),
+            Some(("/usr/local/bin/ccs", "hook ask-done"))
+        );
+        assert_eq!(split_ccs_command("ccs hook ask"), Some(("ccs", "hook ask")));
+        assert_eq!(split_ccs_command("/bin/other hook stop"), None);
+        assert_eq!(split_ccs_command("afplay sound.aiff"), None);
+    }
+
+    #[test]
+    fn test_repair_hook_paths_rewrites_stale_bin() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        fs::write(
+            &path,
+            r#"{"hooks":{"Stop":[{"matcher":"*","hooks":[{"type":"command","command":"/old/bin/ccs hook stop","async":true,"timeout":5}]}]}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(repair_hook_paths(&path, "/new/bin/ccs").unwrap(), 1);
+
+        let parsed: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let stop = parsed["hooks"]["Stop"].as_array().unwrap();
+        assert_eq!(stop.len(), 1);
+        assert_eq!(stop[0]["matcher"].as_str().unwrap(), "*");
+        assert_eq!(
+            stop[0]["hooks"][0]["command"].as_str().unwrap(),
+            "/new/bin/ccs hook stop"
+        );
+        assert_eq!(stop[0]["hooks"][0]["timeout"].as_u64().unwrap(), 5);
+    }
+
+    #[test]
+    fn test_install_hooks_replaces_stale_bin_without_duplicating() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        fs::write(
+            &path,
+            r#"{"hooks":{"Stop":[{"matcher":"*","hooks":[{"type":"command","command":"/old/bin/ccs hook stop"}]},{"matcher":"*","hooks":[{"type":"command","command":"/new/bin/ccs hook stop"}]}]}}"#,
+        )
+        .unwrap();
+
+        install_hooks_with_bin(&path, "/new/bin/ccs", &HooksConfig::default()).unwrap();
+
+        let parsed: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let hooks = parsed["hooks"].as_object().unwrap();
+        assert_eq!(hooks["Stop"].as_array().unwrap().len(), 1);
+        assert_eq!(hooks["PreToolUse"].as_array().unwrap().len(), 1);
+        assert!(stale_hook_commands(&path, "/new/bin/ccs").is_empty());
+    }
+
+    #[test]
+    fn test_repair_hook_paths_keeps_other_hooks_in_a_duplicate_entry() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        fs::write(
+            &path,
+            r#"{"hooks":{"Stop":[{"matcher":"*","hooks":[{"command":"/new/bin/ccs hook stop"}]},{"matcher":"*","hooks":[{"command":"afplay done.aiff"},{"command":"/old/bin/ccs hook stop"}]},{"matcher":"*","hooks":[{"command":"/old/bin/ccs hook stop"}]}]}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(repair_hook_paths(&path, "/new/bin/ccs").unwrap(), 2);
+
+        let parsed: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let stop = parsed["hooks"]["Stop"].as_array().unwrap();
+        assert_eq!(stop.len(), 2);
+        assert_eq!(stop[0]["hooks"][0]["command"], "/new/bin/ccs hook stop");
+        let mixed = stop[1]["hooks"].as_array().unwrap();
+        assert_eq!(mixed.len(), 1);
+        assert_eq!(mixed[0]["command"], "afplay done.aiff");
+    }
+
     #[test]
     fn test_install_hooks_upgrades_old_install() {
         let dir = tempfile::tempdir().unwrap();
@@ -273,7 +677,7 @@ mod tests {
         )
         .unwrap();
 
-        install_hooks_with_bin(&path, "ccs").unwrap();
+        install_hooks_with_bin(&path, "ccs", &HooksConfig::default()).unwrap();
 
         let content = fs::read_to_string(&path).unwrap();
         let parsed: Value = serde_json::from_str(&content).unwrap();
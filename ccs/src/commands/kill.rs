@@ -1,24 +1,101 @@
-use crate::colors::*;
-use crate::tmux;
+use std::path::Path;
 
-pub fn run(name: &str) -> Result<(), String> {
-    if !tmux::has_session() {
-        println!("{ANSI_OVERLAY}No active ccs session.{ANSI_RESET}");
+use crate::colors::{self, Ansi};
+use crate::config::Config;
+use crate::git;
+use crate::tmux::{self, WindowInfo};
+
+// ── Helpers ──
+
+/// The window `target` refers to. Names win: a number only selects by tmux
+/// window index when no window is named that, so a tab called "2" is still
+/// reachable by name.
+fn find<'a>(windows: &'a [WindowInfo], target: &str) -> Option<&'a WindowInfo> {
+    windows.iter().find(|w| w.name == target).or_else(|| {
+        let index: u32 = target.parse().ok()?;
+        windows.iter().find(|w| w.index == index)
+    })
+}
+
+// ── Public API ──
+
+/// Kill one session tab, by name or else by window index.
+pub fn run(session: &str, target: &str, remove_worktree: bool) -> Result<(), String> {
+    let Ansi {
+        peach,
+        overlay,
+        reset,
+        ..
+    } = &colors::ansi();
+    if !tmux::has_session(session) {
+        println!("{overlay}No active ccs session.{reset}");
         return Err(String::new());
     }
 
-    tmux::kill_window(name)?;
-    println!("Killed: {ANSI_PEACH}{name}{ANSI_RESET}");
+    let windows = tmux::list_windows(session)?;
+    let Some(win) = find(&windows, target) else {
+        return Err(format!("No session named '{peach}{target}{reset}'."));
+    };
+    let name = &win.name;
+
+    // Find the worktree before the window (and its cwd) is gone
+    let worktree = if remove_worktree {
+        let dir = Path::new(&win.pane_path);
+        if !git::is_linked_worktree(dir) {
+            return Err(format!(
+                "'{}' is not a linked git worktree; not killing {peach}{name}{reset}.",
+                win.pane_path
+            ));
+        }
+        git::toplevel(dir)
+    } else {
+        None
+    };
+
+    if name == target {
+        tmux::kill_window(session, name)?;
+    } else {
+        tmux::kill_window_by_index(session, win.index)?;
+    }
+    // Killing the last window ends the session, leaving nothing to renumber
+    if Config::load().renumber_on_kill && tmux::has_session(session) {
+        tmux::renumber_windows(session)?;
+    }
+    println!("Killed: {peach}{name}{reset}");
+    if let Some(path) = worktree {
+        git::remove_worktree(&path)?;
+        println!("Removed worktree: {}", path.display());
+    }
     Ok(())
 }
 
-pub fn run_all() -> Result<(), String> {
-    if !tmux::has_session() {
-        println!("{ANSI_OVERLAY}No active ccs session.{ANSI_RESET}");
+pub fn run_all(session: &str) -> Result<(), String> {
+    let Ansi { overlay, reset, .. } = &colors::ansi();
+    if !tmux::has_session(session) {
+        println!("{overlay}No active ccs session.{reset}");
         return Err(String::new());
     }
 
-    tmux::kill_session()?;
+    tmux::kill_session(session)?;
     println!("Killed all sessions.");
     Ok(())
 }
+
+// ── Tests ──
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tmux::test_window as window;
+
+    #[test]
+    fn test_find_prefers_name_over_index() {
+        let windows = [window(0, "api"), window(2, "web"), window(3, "2")];
+        assert_eq!(find(&windows, "web").map(|w| w.index), Some(2));
+        // "2" is a window name, so it beats window index 2
+        assert_eq!(find(&windows, "2").map(|w| w.index), Some(3));
+        assert_eq!(find(&windows, "0").map(|w| w.index), Some(0));
+        assert!(find(&windows, "1").is_none());
+        assert!(find(&windows, "docs").is_none());
+    }
+}
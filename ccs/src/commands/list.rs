@@ -1,29 +1,49 @@
-use crate::colors::*;
-use crate::tmux;
+use crate::colors::{self, Ansi};
+use crate::paths;
+use crate::tmux::{self, WindowInfo};
 
-pub fn run() -> Result<(), String> {
-    if !tmux::has_session() {
-        println!("{ANSI_OVERLAY}No active ccs session.{ANSI_RESET}");
-        return Err(String::new());
-    }
+// ── Public API ──
 
-    let windows = tmux::list_windows()?;
-    let home = std::env::var("HOME").unwrap_or_default();
-
-    for w in &windows {
-        let dir = w.pane_path.replace(&home, "~");
+/// One line per window: the active one highlighted, each with its directory.
+/// `numbered` prefixes 1-based positions for `ccs resume --pick`.
+pub fn print_windows(windows: &[WindowInfo], numbered: bool) {
+    let Ansi {
+        peach,
+        overlay,
+        surface,
+        subtext,
+        bold,
+        reset,
+        ..
+    } = &colors::ansi();
+    for (i, w) in windows.iter().enumerate() {
+        let dir = paths::tilde(&w.pane_path);
+        let number = if numbered {
+            format!("{overlay}{:>2}{reset} ", i + 1)
+        } else {
+            String::new()
+        };
         if w.is_active {
             println!(
-                "  {ANSI_PEACH}●{ANSI_RESET} {ANSI_PEACH}{ANSI_BOLD}{}{ANSI_RESET}  {ANSI_SUBTEXT}{dir}{ANSI_RESET}",
+                "  {number}{peach}●{reset} {peach}{bold}{}{reset}  {subtext}{dir}{reset}",
                 w.name
             );
         } else {
             println!(
-                "  {ANSI_OVERLAY}·{ANSI_RESET} {ANSI_OVERLAY}{}{ANSI_RESET}  {ANSI_SURFACE}{dir}{ANSI_RESET}",
+                "  {number}{overlay}·{reset} {overlay}{}{reset}  {surface}{dir}{reset}",
                 w.name
             );
         }
     }
+}
+
+pub fn run(session: &str) -> Result<(), String> {
+    let Ansi { overlay, reset, .. } = &colors::ansi();
+    if !tmux::has_session(session) {
+        println!("{overlay}No active ccs session.{reset}");
+        return Err(String::new());
+    }
 
+    print_windows(&tmux::list_windows(session)?, false);
     Ok(())
 }
@@ -0,0 +1,11 @@
+use clap::CommandFactory;
+
+use crate::cli::Cli;
+
+/// Print the ccs(1) man page as roff, derived from the clap definitions
+/// (e.g. `ccs man > ccs.1`).
+pub fn run() -> Result<(), String> {
+    clap_mangen::Man::new(Cli::command())
+        .render(&mut std::io::stdout())
+        .map_err(|e| format!("write man page: {e}"))
+}
@@ -1,6 +1,18 @@
+pub mod count;
+pub mod cycle;
+pub mod doctor;
+pub mod events;
+pub mod focus;
 pub mod hook;
+pub mod info;
 pub mod init;
 pub mod kill;
 pub mod list;
+pub mod man;
+pub mod prune;
+pub mod recent;
 pub mod resume;
+pub mod send;
 pub mod start;
+pub mod status;
+pub mod swap;
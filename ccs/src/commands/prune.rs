@@ -0,0 +1,119 @@
+// ── Event file cleanup ──
+//
+// Event files are keyed by Claude session id and never removed by the hooks, so
+// they pile up for long-dead sessions. A file is stale when the pane its last
+// event came from no longer exists on the tmux server, or (with --older-than)
+// when it hasn't been written to in that many days.
+
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use crate::colors::{self, Ansi};
+use crate::paths;
+use crate::sidebar::state;
+use crate::tmux;
+
+// ── Helpers ──
+
+fn is_stale(path: &Path, live_panes: &[String], max_age: Option<Duration>) -> bool {
+    if let Some(pane_id) = state::last_pane_id(path)
+        && !live_panes.contains(&pane_id)
+    {
+        return true;
+    }
+
+    let Some(max_age) = max_age else {
+        return false;
+    };
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age > max_age)
+}
+
+/// Remove stale `.jsonl` files from `dir`. Returns how many were removed.
+fn prune_dir(dir: &Path, live_panes: &[String], max_age: Option<Duration>) -> usize {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+
+    let mut removed = 0;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
+            continue;
+        }
+        if is_stale(&path, live_panes, max_age) && fs::remove_file(&path).is_ok() {
+            removed += 1;
+        }
+    }
+    removed
+}
+
+// ── Public API ──
+
+pub fn run(older_than_days: Option<u64>) -> Result<(), String> {
+    // Without a reliable pane list every pane-tagged file would look stale
+    let live_panes = tmux::list_all_pane_ids()
+        .map_err(|e| format!("{e}\nNot pruning: can't list live panes."))?;
+    let max_age = older_than_days.map(|days| Duration::from_secs(days * 24 * 60 * 60));
+
+    let removed = prune_dir(&paths::events_dir()?, &live_panes, max_age);
+    let plural = if removed == 1 { "" } else { "s" };
+    let Ansi { peach, reset, .. } = &colors::ansi();
+    println!("Removed {peach}{removed}{reset} event file{plural}.");
+    Ok(())
+}
+
+// ── Tests ──
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prune_dead_panes() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("live.jsonl"),
+            "{\"state\":\"idle\",\"cwd\":\"/p\",\"pane_id\":\"%1\",\"ts\":1}\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("dead.jsonl"),
+            "{\"state\":\"idle\",\"cwd\":\"/p\",\"pane_id\":\"%9\",\"ts\":1}\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("notes.txt"), "keep").unwrap();
+
+        let removed = prune_dir(dir.path(), &["%1".to_string()], None);
+
+        assert_eq!(removed, 1);
+        assert!(dir.path().join("live.jsonl").exists());
+        assert!(!dir.path().join("dead.jsonl").exists());
+        assert!(dir.path().join("notes.txt").exists());
+    }
+
+    #[test]
+    fn test_prune_by_age_only_with_flag() {
+        let dir = tempfile::tempdir().unwrap();
+        // No pane id — only age can make this stale
+        let path = dir.path().join("outside-tmux.jsonl");
+        fs::write(&path, "{\"state\":\"idle\",\"cwd\":\"/p\",\"ts\":1}\n").unwrap();
+        let two_days_ago = SystemTime::now() - Duration::from_secs(2 * 24 * 60 * 60);
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(two_days_ago)
+            .unwrap();
+
+        let week = Duration::from_secs(7 * 24 * 60 * 60);
+        let day = Duration::from_secs(24 * 60 * 60);
+        assert_eq!(prune_dir(dir.path(), &[], None), 0);
+        assert_eq!(prune_dir(dir.path(), &[], Some(week)), 0);
+        assert_eq!(prune_dir(dir.path(), &[], Some(day)), 1);
+    }
+}
@@ -0,0 +1,9 @@
+use crate::history;
+
+/// Print directories used with `ccs start`, newest first, one per line.
+pub fn run() -> Result<(), String> {
+    for dir in history::load() {
+        println!("{dir}");
+    }
+    Ok(())
+}
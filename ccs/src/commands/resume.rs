@@ -1,16 +1,83 @@
-use crate::colors::*;
+use std::io::{self, Write};
+
+use crate::colors::{self, Ansi};
+use crate::commands::list;
 use crate::tmux;
 
-pub fn run() -> Result<(), String> {
-    if !tmux::has_session() {
+// ── Helpers ──
+
+/// Parse a 1-based pick out of `count` windows. Empty input keeps the current
+/// window (None).
+fn parse_choice(input: &str, count: usize) -> Result<Option<usize>, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(None);
+    }
+    match input.parse::<usize>() {
+        Ok(n) if (1..=count).contains(&n) => Ok(Some(n - 1)),
+        _ => Err(format!("'{input}' is not a number from 1 to {count}")),
+    }
+}
+
+/// List the windows and ask which to land on. A single window needs no prompt.
+fn pick(session: &str) -> Result<(), String> {
+    let windows = tmux::list_windows(session)?;
+    if windows.len() < 2 {
+        return Ok(());
+    }
+
+    list::print_windows(&windows, true);
+    print!("Session number (enter for current): ");
+    let _ = io::stdout().flush();
+    let mut input = String::new();
+    io::stdin()
+        .read_line(&mut input)
+        .map_err(|e| format!("read stdin: {e}"))?;
+
+    if let Some(i) = parse_choice(&input, windows.len())? {
+        tmux::select_window(&windows[i].window_id)?;
+    }
+    Ok(())
+}
+
+// ── Public API ──
+
+pub fn run(session: &str, pick_window: bool) -> Result<(), String> {
+    let Ansi {
+        peach,
+        overlay,
+        reset,
+        ..
+    } = &colors::ansi();
+    if !tmux::has_session(session) {
         return Err(format!(
-            "{ANSI_OVERLAY}No active ccs session.{ANSI_RESET} Run {ANSI_PEACH}ccs start{ANSI_RESET} to create one."
+            "{overlay}No active ccs session.{reset} Run {peach}ccs start{reset} to create one."
         ));
     }
 
+    if pick_window {
+        pick(session)?;
+    }
+
     if tmux::is_inside_tmux() {
-        tmux::switch_client()
+        tmux::switch_client(session)
     } else {
-        tmux::attach()
+        tmux::attach(session)
+    }
+}
+
+// ── Tests ──
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_choice() {
+        assert_eq!(parse_choice("2\n", 3), Ok(Some(1)));
+        assert_eq!(parse_choice("  \n", 3), Ok(None));
+        assert!(parse_choice("0", 3).is_err());
+        assert!(parse_choice("4", 3).is_err());
+        assert!(parse_choice("two", 3).is_err());
     }
 }
@@ -0,0 +1,53 @@
+use crate::colors::{self, Ansi};
+use crate::config::Config;
+use crate::sidebar::state;
+use crate::tmux;
+
+/// Type `text` into a session tab's claude pane, optionally pressing Enter.
+pub fn run(session: &str, name: &str, text: &str, enter: bool) -> Result<(), String> {
+    let Ansi {
+        peach,
+        overlay,
+        reset,
+        ..
+    } = &colors::ansi();
+    if !tmux::has_session(session) {
+        println!("{overlay}No active ccs session.{reset}");
+        return Err(String::new());
+    }
+
+    let windows = tmux::list_windows(session)?;
+    let Some(win) = windows.iter().find(|w| w.name == name) else {
+        return Err(format!("No session named '{peach}{name}{reset}'."));
+    };
+
+    tmux::send_keys(&win.pane_id, text, enter)?;
+    println!("Sent to: {peach}{name}{reset}");
+    Ok(())
+}
+
+/// Type `text` into every session tab still running Claude.
+pub fn broadcast(session: &str, text: &str, enter: bool) -> Result<(), String> {
+    let Ansi { overlay, reset, .. } = &colors::ansi();
+    if !tmux::has_session(session) {
+        println!("{overlay}No active ccs session.{reset}");
+        return Err(String::new());
+    }
+
+    let detect = Config::load().detect;
+    let mut sent = 0;
+    for win in tmux::list_windows(session)? {
+        // Claude exited from this pane; nothing to receive it
+        if win.dead || state::claude_exited(&win.command, &detect) {
+            continue;
+        }
+        match tmux::send_keys(&win.pane_id, text, enter) {
+            Ok(()) => sent += 1,
+            Err(e) => eprintln!("{}: {e}", win.name),
+        }
+    }
+
+    let plural = if sent == 1 { "" } else { "s" };
+    println!("Sent to {sent} session{plural}.");
+    Ok(())
+}
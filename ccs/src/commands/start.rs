@@ -1,41 +1,120 @@
 use std::io::{self, Write};
-use std::path::PathBuf;
+use std::path::Path;
+use std::process::{Command, Stdio};
 
-use crate::colors::*;
+use crate::colors::{self, Ansi};
 use crate::commands::init;
+use crate::config::Config;
+use crate::git;
+use crate::history;
+use crate::paths;
 use crate::tmux;
 
+// ── Types ──
+
+/// How `ccs start` picks the directory and launches claude.
+pub struct StartOptions<'a> {
+    pub dir: Option<&'a str>,
+    /// Create `dir` if it doesn't exist.
+    pub create_dir: bool,
+    /// Without `dir`, choose one from the start history with fzf.
+    pub pick_dir: bool,
+    /// Split the current tmux window instead of adding a session tab.
+    pub here: bool,
+    /// Check this branch out in a new worktree beside the repo and start there.
+    pub worktree: Option<&'a str>,
+    /// Focus the new window when adding to an existing session.
+    pub select: bool,
+    pub claude_args: &'a [String],
+}
+
 // ── Helpers ──
 
-fn resolve_sidebar_bin() -> String {
-    // Try to find the binary we're running from (works after `cargo install` or symlink)
-    if let Ok(exe) = std::env::current_exe()
-        && let Ok(canonical) = std::fs::canonicalize(exe)
-    {
-        return canonical.to_string_lossy().to_string();
+/// Absolute path of the session's working directory. With `create`, missing
+/// directories are made first; otherwise a missing one gets its own error.
+fn resolve_dir(dir: &str, create: bool) -> Result<String, String> {
+    let path = Path::new(dir);
+    if create {
+        std::fs::create_dir_all(path).map_err(|e| format!("create directory '{dir}': {e}"))?;
+    } else if !path.exists() {
+        return Err(format!(
+            "directory '{dir}' does not exist (use --create-dir to create it)"
+        ));
+    }
+    if !path.is_dir() {
+        return Err(format!("'{dir}' is not a directory"));
+    }
+    let canonical =
+        std::fs::canonicalize(path).map_err(|e| format!("invalid directory '{dir}': {e}"))?;
+    Ok(canonical.to_string_lossy().to_string())
+}
+
+/// Let the user choose a directory from the start history with fzf. None when
+/// fzf isn't installed or there's no history yet, so the caller can fall back.
+fn pick_dir() -> Result<Option<String>, String> {
+    let dirs = history::load();
+    if dirs.is_empty() {
+        return Ok(None);
+    }
+    let spawned = Command::new("fzf")
+        .args(["--prompt", "dir> ", "--height", "40%", "--reverse"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn();
+    let mut child = match spawned {
+        Ok(child) => child,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("fzf: {e}")),
+    };
+
+    let list: String = dirs
+        .iter()
+        .map(|d| format!("{}\n", paths::tilde(d)))
+        .collect();
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(list.as_bytes())
+            .map_err(|e| format!("fzf: {e}"))?;
     }
-    // Fallback to the expected install location
-    let home = std::env::var("HOME").unwrap_or_default();
-    format!("{home}/.local/bin/ccs")
+    let output = child.wait_with_output().map_err(|e| format!("fzf: {e}"))?;
+    let choice = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !output.status.success() || choice.is_empty() {
+        return Err("No directory picked.".to_string());
+    }
+    Ok(Some(paths::expand(&choice)))
 }
 
-fn settings_path() -> PathBuf {
-    let home = std::env::var("HOME").unwrap_or_default();
-    PathBuf::from(home).join(".claude").join("settings.json")
+/// Check `branch` out in a worktree named after the session, next to the
+/// repo holding `dir` (`../<name>` from its top level). Returns its path.
+fn create_worktree(dir: &str, name: &str, branch: &str) -> Result<String, String> {
+    let Some(top) = git::toplevel(Path::new(dir)) else {
+        return Err(format!(
+            "'{dir}' is not in a git repository; --worktree needs one"
+        ));
+    };
+    let path = top
+        .parent()
+        .ok_or_else(|| format!("'{}' has no parent directory", top.display()))?
+        .join(name);
+    if path.exists() {
+        return Err(format!("'{}' already exists", path.display()));
+    }
+    git::add_worktree(&top, &path, branch)?;
+    Ok(path.to_string_lossy().to_string())
 }
 
 /// Prompt user to install hooks if not already present.
-fn check_hooks() {
-    let path = settings_path();
-    if init::hooks_installed(&path) {
+fn check_hooks(path: &Path) {
+    let Ansi { peach, reset, .. } = &colors::ansi();
+    if init::hooks_installed(path) {
         return;
     }
 
     println!(
         "CCS needs Claude Code hooks to show session status (Working/Idle/Asking).\n\
          This adds two async hooks to ~/.claude/settings.json:\n\
-         {ANSI_PEACH}  UserPromptSubmit{ANSI_RESET}  detects when you send a message\n\
-         {ANSI_PEACH}  Stop{ANSI_RESET}              detects when Claude finishes responding\n"
+         {peach}  UserPromptSubmit{reset}  detects when you send a message\n\
+         {peach}  Stop{reset}              detects when Claude finishes responding\n"
     );
     print!("Add CCS hooks? [Y/n] ");
     let _ = io::stdout().flush();
@@ -47,7 +126,7 @@ fn check_hooks() {
 
     let answer = input.trim().to_lowercase();
     if answer.is_empty() || answer == "y" || answer == "yes" {
-        match init::install_hooks(&path) {
+        match init::install_hooks(path, &Config::load().hooks) {
             Ok(()) => println!("Hooks installed.\n"),
             Err(e) => eprintln!("Failed to install hooks: {e}\n"),
         }
@@ -58,46 +137,155 @@ fn check_hooks() {
 
 // ── Public API ──
 
-pub fn run(name: &str, dir: Option<&str>) -> Result<(), String> {
-    let dir = dir.unwrap_or(".");
-    let dir = std::fs::canonicalize(dir)
-        .map_err(|e| format!("invalid directory '{dir}': {e}"))?
-        .to_string_lossy()
-        .to_string();
-
-    let sidebar_bin = resolve_sidebar_bin();
-    let sidebar_cmd = format!("{sidebar_bin} sidebar");
-
-    // First-run: prompt to install hooks if needed
-    check_hooks();
+/// Everything the claude pane is launched with, in order: flags ccs adds
+/// itself, then the user's pass-through arguments. Pane respawns reuse it.
+pub fn claude_args(continue_conversation: bool, extra: Vec<String>) -> Vec<String> {
+    let mut args = Vec::new();
+    if continue_conversation {
+        args.push("--continue".to_string());
+    }
+    args.extend(extra);
+    args
+}
 
-    if tmux::has_session() {
+pub fn run(session: &str, name: &str, opts: &StartOptions) -> Result<(), String> {
+    let Ansi {
+        peach,
+        yellow,
+        reset,
+        ..
+    } = &colors::ansi();
+    let picked = match opts.dir {
+        None if opts.pick_dir => pick_dir()?,
+        _ => None,
+    };
+    let dir = picked.as_deref().or(opts.dir).unwrap_or(".");
+    let mut dir = resolve_dir(&paths::expand(dir), opts.create_dir)?;
+    // Refuse before anything is created, so a rejected start leaves no worktree behind
+    if opts.here {
+        if !tmux::is_inside_tmux() {
+            return Err("--here only works from inside tmux".to_string());
+        }
+    } else {
+        if tmux::is_foreign_session(session) {
+            return Err(format!(
+                "A tmux session named '{peach}{session}{reset}' already exists and wasn't created by ccs. \
+                 Use {peach}--session <name>{reset} to pick another name."
+            ));
+        }
         // Reject duplicate window names
-        let names = tmux::list_window_names()?;
-        if names.iter().any(|n| n == name) {
+        if tmux::has_session(session) && tmux::list_window_names(session)?.iter().any(|n| n == name)
+        {
             return Err(format!(
-                "Session '{ANSI_PEACH}{name}{ANSI_RESET}' already exists. Pick a different name."
+                "Session '{peach}{name}{reset}' already exists. Pick a different name."
             ));
         }
+    }
+    if let Some(branch) = opts.worktree {
+        dir = create_worktree(&dir, name, branch)?;
+    }
+    // Best effort: a read-only state dir shouldn't stop the session starting
+    let _ = history::record(&dir);
+    let config = Config::load();
+    if config.warn_non_git && !git::in_work_tree(Path::new(&dir)) {
+        eprintln!(
+            "{yellow}warning:{reset} {} is not inside a git repository",
+            paths::tilde(&dir)
+        );
+    }
+
+    let settings = init::settings_path()?;
+    let sidebar_bin = init::ccs_bin_path();
+    let sidebar_cmd = format!("{sidebar_bin} --session {session} sidebar");
+    let claude_cmd = tmux::claude_command(opts.claude_args);
+    let layout = config.sidebar;
+
+    // First-run: prompt to install hooks if needed
+    check_hooks(&settings);
+
+    if opts.here {
+        // Beside the user's current pane; this one isn't part of the session
+        return tmux::split_here(name, &dir, &claude_cmd);
+    }
 
-        tmux::new_window(name, &dir)?;
-        tmux::setup_layout(name, &dir, &sidebar_cmd)?;
+    if tmux::has_session(session) {
+        let window_id = tmux::new_window(session, name, &dir, &claude_cmd)?;
+        tmux::setup_layout(&window_id, &dir, &sidebar_cmd, &layout)?;
+        if opts.select {
+            tmux::select_window(&window_id)?;
+            if tmux::is_inside_tmux() {
+                tmux::switch_client(session)?;
+            }
+        }
 
         // If outside tmux, attach so the user sees it
         if !tmux::is_inside_tmux() {
-            tmux::attach()?;
+            tmux::attach(session)?;
         }
+    } else if tmux::is_inside_tmux() {
+        // No session, but we're inside another tmux session: build it detached
+        // at this client's size, then hop over
+        tmux::new_session(
+            session,
+            name,
+            &dir,
+            &sidebar_cmd,
+            &claude_cmd,
+            &layout,
+            true,
+        )?;
+        tmux::switch_client(session)?;
     } else {
-        // No session — create from scratch. Must run outside tmux for proper dimensions.
-        if tmux::is_inside_tmux() {
-            return Err(format!(
-                "No ccs session exists. Run from outside tmux first:\n  \
-                 {ANSI_PEACH}ccs start{ANSI_RESET} {name} {dir}"
-            ));
-        }
-
-        tmux::new_session(name, &dir, &sidebar_cmd)?;
+        // No session — create from scratch and attach
+        tmux::new_session(
+            session,
+            name,
+            &dir,
+            &sidebar_cmd,
+            &claude_cmd,
+            &layout,
+            false,
+        )?;
     }
 
     Ok(())
 }
+
+// ── Tests ──
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_claude_args_continue_comes_first() {
+        let args = claude_args(true, vec!["--model".into(), "opus".into()]);
+        assert_eq!(args, ["--continue", "--model", "opus"]);
+        assert!(claude_args(false, Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn test_resolve_dir_missing_without_create() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("new");
+        let err = resolve_dir(dir.to_str().unwrap(), false).unwrap_err();
+        assert!(err.contains("does not exist"));
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn test_create_worktree_outside_git() {
+        let tmp = tempfile::tempdir().unwrap();
+        let err = create_worktree(tmp.path().to_str().unwrap(), "feat", "feat").unwrap_err();
+        assert!(err.contains("not in a git repository"));
+    }
+
+    #[test]
+    fn test_resolve_dir_creates_nested() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("a/b");
+        let resolved = resolve_dir(dir.to_str().unwrap(), true).unwrap();
+        assert!(dir.is_dir());
+        assert!(Path::new(&resolved).is_absolute());
+    }
+}
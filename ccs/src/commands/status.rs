@@ -0,0 +1,133 @@
+// ── One-line status for tmux status-right ──
+//
+// `set -g status-right '#(ccs status)'` shows every session's state in the
+// tmux status bar. One detect pass, then exit — tmux runs this on every
+// status-interval, so it must stay cheap and print nothing when there's no
+// session. --json prints a waybar/polybar block instead:
+//
+//   {"text": "review:✳ api:◆", "tooltip": "review: working\napi: asking", "class": "asking"}
+
+use crate::sidebar::state::{StateDetector, WindowState};
+use crate::tmux::{self, WindowInfo};
+
+// ── Constants ──
+
+pub const DEFAULT_FORMAT: &str = "{name}:{glyph}";
+
+// ── Helpers ──
+
+fn glyph(state: WindowState) -> &'static str {
+    match state {
+        WindowState::Fresh => "\u{00b7}",
+        WindowState::Working | WindowState::Compacting => "\u{2733}",
+        WindowState::Asking => "\u{25c6}",
+        WindowState::Limited => "!",
+        WindowState::Idle => "\u{2713}",
+        WindowState::Done => "\u{25cb}",
+    }
+}
+
+/// Bar class for the most urgent state across sessions: asking > working > idle.
+fn class(states: &[WindowState]) -> &'static str {
+    if states.contains(&WindowState::Asking) {
+        "asking"
+    } else if states
+        .iter()
+        .any(|s| matches!(s, WindowState::Working | WindowState::Compacting))
+    {
+        "working"
+    } else {
+        "idle"
+    }
+}
+
+/// Fill `{name}`, `{index}`, `{state}`, and `{glyph}` in `format` for one window.
+fn render(format: &str, win: &WindowInfo, state: WindowState) -> String {
+    format
+        .replace("{name}", &win.name)
+        .replace("{index}", &win.index.to_string())
+        .replace("{state}", state.name())
+        .replace("{glyph}", glyph(state))
+}
+
+// ── Public API ──
+
+pub fn run(session: &str, format: &str, separator: &str, json: bool) -> Result<(), String> {
+    let windows = if tmux::has_session(session) {
+        tmux::list_windows(session)?
+    } else if json {
+        Vec::new()
+    } else {
+        return Ok(());
+    };
+
+    let detected = StateDetector::new().detect(&windows);
+    let states: Vec<WindowState> = windows
+        .iter()
+        .map(|win| {
+            detected
+                .get(&win.index)
+                .copied()
+                .unwrap_or(WindowState::Fresh)
+        })
+        .collect();
+    let text = windows
+        .iter()
+        .zip(&states)
+        .map(|(win, state)| render(format, win, *state))
+        .collect::<Vec<_>>()
+        .join(separator);
+
+    if json {
+        let tooltip = windows
+            .iter()
+            .zip(&states)
+            .map(|(win, state)| format!("{}: {}", win.name, state.name()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let block = serde_json::json!({
+            "text": text,
+            "tooltip": tooltip,
+            "class": class(&states),
+        });
+        println!("{block}");
+    } else {
+        println!("{text}");
+    }
+    Ok(())
+}
+
+// ── Tests ──
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tmux::test_window as window;
+
+    #[test]
+    fn test_render_default_format() {
+        let win = window(1, "review");
+        assert_eq!(
+            render(DEFAULT_FORMAT, &win, WindowState::Working),
+            "review:\u{2733}"
+        );
+    }
+
+    #[test]
+    fn test_class_priority() {
+        use WindowState::*;
+        assert_eq!(class(&[Idle, Working, Asking]), "asking");
+        assert_eq!(class(&[Done, Working, Idle]), "working");
+        assert_eq!(class(&[Fresh, Limited]), "idle");
+        assert_eq!(class(&[]), "idle");
+    }
+
+    #[test]
+    fn test_render_all_placeholders() {
+        let win = window(3, "api");
+        assert_eq!(
+            render("#{index} {name} [{state}]", &win, WindowState::Asking),
+            "#3 api [asking]"
+        );
+    }
+}
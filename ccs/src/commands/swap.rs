@@ -0,0 +1,27 @@
+use crate::colors::{self, Ansi};
+use crate::tmux;
+
+/// Swap two session tabs' positions in the tab bar.
+pub fn run(session: &str, a: &str, b: &str) -> Result<(), String> {
+    let Ansi {
+        peach,
+        overlay,
+        reset,
+        ..
+    } = &colors::ansi();
+    if !tmux::has_session(session) {
+        println!("{overlay}No active ccs session.{reset}");
+        return Err(String::new());
+    }
+
+    let names = tmux::list_window_names(session)?;
+    for name in [a, b] {
+        if !names.iter().any(|n| n == name) {
+            return Err(format!("No session named '{peach}{name}{reset}'."));
+        }
+    }
+
+    tmux::swap_windows(session, a, b)?;
+    println!("Swapped: {peach}{a}{reset} and {peach}{b}{reset}");
+    Ok(())
+}
@@ -0,0 +1,347 @@
+// ── User configuration ──
+//
+// Optional settings read from ~/.ccs/config.toml, or $XDG_CONFIG_HOME/ccs/
+// (see paths.rs). Every field has a default, so a missing or partial file
+// behaves exactly like no file at all.
+//
+//   theme = "mocha"  # mocha, latte, gruvbox or nord ($CCS_THEME overrides)
+//   renumber_on_kill = false  # close index gaps (0, 2, 3 -> 0, 1, 2) after `ccs kill`
+//   warn_non_git = false      # warn when `ccs start` runs outside a git work tree
+//
+//   [hooks]
+//   timeout = 10     # seconds Claude Code waits for each ccs hook
+//   async = true     # run hooks without blocking Claude
+//
+//   [sidebar]
+//   ascii = false    # ASCII-only glyphs for limited terminals ($CCS_ASCII overrides)
+//   blink_asking = true  # pulse sessions waiting on you; false for no motion
+//   auto_close_done_after = 600  # kill windows Done this many seconds (off by default;
+//                                # never the selected one)
+//   group_by_dir = false  # group sessions under a header per directory
+//   hide_done = false  # leave Done sessions out of the list (they stay in tmux)
+//   layout = "columns"  # "columns": sidebar beside the terminal pane
+//                       # "stacked": full-width rows under claude, for tall terminals
+//   percent = 30     # sidebar size, as a share of the terminal area
+//   pin_asking = false  # float sessions waiting on you to the top, whatever the sort
+//   side = "right"   # "left"/"right" of the terminal (above/below when stacked)
+//   spinner = "braille"  # "braille", "dots", "line", "arc", or frames like ["-", "+"]
+//   sort = "index"   # "index" (tmux order), "name", or "state" (asking first)
+//   terminal_command = "lazygit"  # run in the terminal pane instead of tmux's
+//                                 # default-shell; every ccs window restarts
+//                                 # it in the window's directory if it exits
+//   window_status = false  # prefix tmux window names with ✳ (working) / ◆ (asking)
+//
+//   [sidebar.labels]  # status text per state, replacing the built-in one;
+//   asking = "waiting…"  # "" shows nothing. Also fresh, idle (none by
+//   done = "done"        # default, e.g. "ready"), limited, compacting
+//
+//   [keys]           # sidebar keys; each action takes a list of key specs
+//   up = ["up", "k"]         # like "j", "?", "enter", "esc", "tab", "space",
+//   down = ["down", "j"]     # "home", "end", "pageup", "pagedown", "backspace"
+//   select = ["enter"]       # or "ctrl-n"; invalid specs fall back to the default
+//   quit = ["q"]
+//   help = ["?"]
+//
+//   [events]
+//   max_bytes = 1048576   # trim a session's event file past this size
+//
+//   [detect]
+//   capture_lines = 12    # trailing non-blank pane lines kept for the
+//                         # prompt/limit/tool checks (lines cut at 256 chars)
+//   done_commands = ["zsh", "bash", "fish"]  # pane commands meaning Claude exited
+//   claude_commands = ["node", "claude"]     # anything else also counts as exited;
+//                                            # [] to trust every non-done command
+
+use serde::Deserialize;
+
+use crate::paths;
+
+// ── Types ──
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct Config {
+    pub theme: Option<String>,
+    pub renumber_on_kill: bool,
+    pub warn_non_git: bool,
+    pub hooks: HooksConfig,
+    pub sidebar: SidebarConfig,
+    pub keys: KeysConfig,
+    pub events: EventsConfig,
+    pub detect: DetectConfig,
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct HooksConfig {
+    pub timeout: u64,
+    #[serde(rename = "async")]
+    pub run_async: bool,
+}
+
+impl Default for HooksConfig {
+    fn default() -> Self {
+        Self {
+            timeout: 5,
+            run_async: true,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct SidebarConfig {
+    pub ascii: bool,
+    pub blink_asking: bool,
+    pub auto_close_done_after: Option<u64>,
+    pub group_by_dir: bool,
+    pub hide_done: bool,
+    pub labels: StatusLabels,
+    pub layout: Layout,
+    pub percent: u8,
+    pub pin_asking: bool,
+    pub side: Side,
+    pub spinner: Option<Spinner>,
+    pub sort: SortOrder,
+    pub terminal_command: Option<String>,
+    pub window_status: bool,
+}
+
+impl Default for SidebarConfig {
+    fn default() -> Self {
+        Self {
+            ascii: false,
+            blink_asking: true,
+            auto_close_done_after: None,
+            group_by_dir: false,
+            hide_done: false,
+            labels: StatusLabels::default(),
+            layout: Layout::Columns,
+            percent: 30,
+            pin_asking: false,
+            side: Side::Right,
+            spinner: None,
+            sort: SortOrder::Index,
+            terminal_command: None,
+            window_status: false,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Layout {
+    #[default]
+    Columns,
+    Stacked,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Left,
+    #[default]
+    Right,
+}
+
+/// Working spinner: a preset name or a custom list of frames.
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum Spinner {
+    Preset(String),
+    Frames(Vec<String>),
+}
+
+/// Sidebar status text overrides; None keeps the built-in label. Working has
+/// none, since it shows the spinner.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct StatusLabels {
+    pub fresh: Option<String>,
+    pub asking: Option<String>,
+    pub limited: Option<String>,
+    pub idle: Option<String>,
+    pub done: Option<String>,
+    pub compacting: Option<String>,
+}
+
+/// Order of sessions in the sidebar list.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    #[default]
+    Index,
+    Name,
+    State,
+}
+
+/// Sidebar key specs per action. An empty list means the built-in default.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct KeysConfig {
+    pub up: Vec<String>,
+    pub down: Vec<String>,
+    pub select: Vec<String>,
+    pub quit: Vec<String>,
+    pub help: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct EventsConfig {
+    pub max_bytes: u64,
+}
+
+impl Default for EventsConfig {
+    fn default() -> Self {
+        Self {
+            max_bytes: 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct DetectConfig {
+    pub capture_lines: usize,
+    pub done_commands: Vec<String>,
+    pub claude_commands: Vec<String>,
+}
+
+impl Default for DetectConfig {
+    fn default() -> Self {
+        let names = |names: &[&str]| names.iter().map(|n| n.to_string()).collect();
+        Self {
+            capture_lines: 12,
+            done_commands: names(&["zsh", "bash", "fish"]),
+            claude_commands: names(&["node", "claude"]),
+        }
+    }
+}
+
+// ── Public API ──
+
+impl Config {
+    /// Load the config file. A missing file yields defaults; a malformed one
+    /// warns on stderr and also yields defaults rather than failing the command.
+    pub fn load() -> Self {
+        let Ok(path) = paths::config_path() else {
+            return Self::default();
+        };
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            return Self::default();
+        };
+        match toml::from_str(&content) {
+            Ok(config) => config,
+            Err(e) => {
+                eprintln!("ccs: ignoring {}: {e}", path.display());
+                Self::default()
+            }
+        }
+    }
+}
+
+// ── Tests ──
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_config_uses_defaults() {
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.hooks.timeout, 5);
+        assert!(config.hooks.run_async);
+    }
+
+    #[test]
+    fn test_partial_hooks_section() {
+        let config: Config = toml::from_str("[hooks]\ntimeout = 30\n").unwrap();
+        assert_eq!(config.hooks.timeout, 30);
+        assert!(config.hooks.run_async);
+    }
+
+    #[test]
+    fn test_sidebar_side_and_percent() {
+        let config: Config = toml::from_str("[sidebar]\nside = \"left\"\npercent = 20\n").unwrap();
+        assert_eq!(config.sidebar.side, Side::Left);
+        assert_eq!(config.sidebar.percent, 20);
+        assert!(toml::from_str::<Config>("[sidebar]\nside = \"top\"\n").is_err());
+    }
+
+    #[test]
+    fn test_sidebar_sort() {
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.sidebar.sort, SortOrder::Index);
+        let config: Config = toml::from_str("[sidebar]\nsort = \"state\"\n").unwrap();
+        assert_eq!(config.sidebar.sort, SortOrder::State);
+    }
+
+    #[test]
+    fn test_sidebar_labels() {
+        let config: Config = toml::from_str("").unwrap();
+        assert!(!config.sidebar.hide_done);
+        assert_eq!(config.sidebar.labels.done, None);
+        let config: Config =
+            toml::from_str("[sidebar]\nhide_done = true\n\n[sidebar.labels]\nidle = \"ready\"\n")
+                .unwrap();
+        assert!(config.sidebar.hide_done);
+        assert_eq!(config.sidebar.labels.idle.as_deref(), Some("ready"));
+        assert_eq!(config.sidebar.labels.asking, None);
+    }
+
+    #[test]
+    fn test_auto_close_is_opt_in() {
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.sidebar.auto_close_done_after, None);
+        let config: Config = toml::from_str("[sidebar]\nauto_close_done_after = 60\n").unwrap();
+        assert_eq!(config.sidebar.auto_close_done_after, Some(60));
+    }
+
+    #[test]
+    fn test_spinner_preset_or_frames() {
+        let config: Config = toml::from_str("[sidebar]\nspinner = \"arc\"\n").unwrap();
+        assert_eq!(
+            config.sidebar.spinner,
+            Some(Spinner::Preset("arc".to_string()))
+        );
+        let config: Config = toml::from_str("[sidebar]\nspinner = [\"-\", \"+\"]\n").unwrap();
+        assert_eq!(
+            config.sidebar.spinner,
+            Some(Spinner::Frames(vec!["-".to_string(), "+".to_string()]))
+        );
+    }
+
+    #[test]
+    fn test_keys_section() {
+        let config: Config = toml::from_str("[keys]\nup = [\"ctrl-p\"]\n").unwrap();
+        assert_eq!(config.keys.up, vec!["ctrl-p"]);
+        assert!(config.keys.down.is_empty());
+    }
+
+    #[test]
+    fn test_renumber_on_kill_is_opt_in() {
+        let config: Config = toml::from_str("").unwrap();
+        assert!(!config.renumber_on_kill);
+        let config: Config = toml::from_str(
+            "renumber_on_kill = true
+",
+        )
+        .unwrap();
+        assert!(config.renumber_on_kill);
+    }
+
+    #[test]
+    fn test_detect_command_sets() {
+        let config: Config = toml::from_str("[detect]\nclaude_commands = []\n").unwrap();
+        assert!(config.detect.claude_commands.is_empty());
+        assert_eq!(config.detect.done_commands, vec!["zsh", "bash", "fish"]);
+        assert_eq!(config.detect.capture_lines, 12);
+    }
+
+    #[test]
+    fn test_theme_is_top_level() {
+        let config: Config = toml::from_str("theme = \"nord\"\n[hooks]\n").unwrap();
+        assert_eq!(config.theme.as_deref(), Some("nord"));
+    }
+}
@@ -0,0 +1,128 @@
+// ── git Command wrappers ──
+//
+// Just enough git for `ccs start --worktree`, `ccs kill --remove-worktree`, and
+// the `warn_non_git` check.
+// Errors carry git's own stderr so a refused worktree operation explains itself.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// ── Helpers ──
+
+fn git(dir: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => "git is not installed or not on PATH".to_string(),
+            _ => format!("git: {e}"),
+        })?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("git: {}", stderr.trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn ref_exists(dir: &Path, name: &str) -> bool {
+    git(dir, &["rev-parse", "--verify", "--quiet", name]).is_ok()
+}
+
+// ── Public API ──
+
+/// Top-level directory of the work tree containing `dir`, or None outside git.
+pub fn toplevel(dir: &Path) -> Option<PathBuf> {
+    git(dir, &["rev-parse", "--show-toplevel"])
+        .ok()
+        .map(PathBuf::from)
+}
+
+/// Whether `dir` is inside a git work tree, found by walking up to a `.git`
+/// entry (a directory, or a file in worktrees and submodules). No git needed.
+pub fn in_work_tree(dir: &Path) -> bool {
+    dir.ancestors().any(|d| d.join(".git").exists())
+}
+
+/// Whether `dir` sits in a linked worktree rather than a repo's main checkout.
+/// git prints the two dirs in different forms (`/repo/.git` vs `../.git` from
+/// a subdirectory), so both are resolved against `dir` before comparing.
+pub fn is_linked_worktree(dir: &Path) -> bool {
+    let Ok(out) = git(dir, &["rev-parse", "--git-dir", "--git-common-dir"]) else {
+        return false;
+    };
+    let mut dirs = out.lines().map(|line| dir.join(line).canonicalize().ok());
+    match (dirs.next().flatten(), dirs.next().flatten()) {
+        (Some(git_dir), Some(common_dir)) => git_dir != common_dir,
+        _ => false,
+    }
+}
+
+/// Check out `branch` in a new worktree at `path`. A branch that exists locally
+/// or on origin is checked out as is; otherwise it's created from HEAD.
+pub fn add_worktree(repo: &Path, path: &Path, branch: &str) -> Result<(), String> {
+    let path = path.to_string_lossy();
+    let known = ref_exists(repo, &format!("refs/heads/{branch}"))
+        || ref_exists(repo, &format!("refs/remotes/origin/{branch}"));
+    if known {
+        git(repo, &["worktree", "add", &path, branch])?;
+    } else {
+        git(repo, &["worktree", "add", "-b", branch, &path])?;
+    }
+    Ok(())
+}
+
+/// Remove the linked worktree at `path`. git refuses if it has local changes.
+pub fn remove_worktree(path: &Path) -> Result<(), String> {
+    git(path, &["worktree", "remove", &path.to_string_lossy()])?;
+    Ok(())
+}
+
+// ── Tests ──
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_in_work_tree_walks_up() {
+        let tmp = tempfile::tempdir().unwrap();
+        let nested = tmp.path().join("repo/src/bin");
+        std::fs::create_dir_all(&nested).unwrap();
+        assert!(!in_work_tree(&nested));
+        // Worktrees and submodules have a .git file rather than a directory
+        std::fs::write(tmp.path().join("repo/.git"), "gitdir: ../main/.git\n").unwrap();
+        assert!(in_work_tree(&nested));
+        assert!(!in_work_tree(tmp.path()));
+    }
+
+    #[test]
+    fn test_is_linked_worktree_from_subdir() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path().join("repo");
+        std::fs::create_dir_all(repo.join("src")).unwrap();
+        git(&repo, &["init", "-q"]).unwrap();
+        git(
+            &repo,
+            &[
+                "-c",
+                "user.name=t",
+                "-c",
+                "user.email=t@t",
+                "commit",
+                "-q",
+                "--allow-empty",
+                "-m",
+                "init",
+            ],
+        )
+        .unwrap();
+        assert!(!is_linked_worktree(&repo));
+        assert!(!is_linked_worktree(&repo.join("src")));
+
+        let linked = tmp.path().join("linked");
+        add_worktree(&repo, &linked, "feature").unwrap();
+        assert!(is_linked_worktree(&linked));
+    }
+}
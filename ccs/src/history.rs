@@ -0,0 +1,84 @@
+// ── Directory history ──
+//
+// `ccs start` records each session's working directory in a plain text file,
+// one absolute path per line, oldest first (~/.ccs/dirs, or
+// $XDG_STATE_HOME/ccs/dirs; see paths.rs). A directory appears once, at its
+// most recent use, and only the last MAX_ENTRIES are kept. `ccs recent`
+// prints it newest first and `ccs start --pick-dir` offers it to fzf.
+
+use std::fs;
+
+use crate::paths;
+
+// ── Constants ──
+
+const MAX_ENTRIES: usize = 100;
+
+// ── Helpers ──
+
+/// Lines of the history file, newest first, each directory listed once.
+fn newest_first(content: &str) -> Vec<String> {
+    let mut dirs: Vec<String> = Vec::new();
+    for line in content.lines().rev() {
+        let line = line.trim();
+        if !line.is_empty() && !dirs.iter().any(|d| d == line) {
+            dirs.push(line.to_string());
+        }
+    }
+    dirs
+}
+
+/// History file content with `dir` moved to the end, capped to `max` entries.
+fn with_entry(content: &str, dir: &str, max: usize) -> String {
+    let mut dirs = newest_first(content);
+    dirs.retain(|d| d != dir);
+    dirs.insert(0, dir.to_string());
+    dirs.truncate(max);
+    dirs.iter().rev().map(|d| format!("{d}\n")).collect()
+}
+
+// ── Public API ──
+
+/// Record `dir` as the most recently used directory.
+pub fn record(dir: &str) -> Result<(), String> {
+    let path = paths::history_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("create {}: {e}", parent.display()))?;
+    }
+    let content = fs::read_to_string(&path).unwrap_or_default();
+    fs::write(&path, with_entry(&content, dir, MAX_ENTRIES))
+        .map_err(|e| format!("write {}: {e}", path.display()))
+}
+
+/// Previously used directories, newest first. Empty if there's no history.
+pub fn load() -> Vec<String> {
+    paths::history_path()
+        .and_then(|path| fs::read_to_string(path).map_err(|e| e.to_string()))
+        .map(|content| newest_first(&content))
+        .unwrap_or_default()
+}
+
+// ── Tests ──
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_newest_first_dedups() {
+        let content = "/code/api\n/code/web\n\n/code/api\n/code/docs\n";
+        assert_eq!(
+            newest_first(content),
+            ["/code/docs", "/code/api", "/code/web"]
+        );
+        assert!(newest_first("").is_empty());
+    }
+
+    #[test]
+    fn test_with_entry_moves_to_end_and_caps() {
+        let content = "/a\n/b\n/c\n";
+        assert_eq!(with_entry(content, "/a", 10), "/b\n/c\n/a\n");
+        assert_eq!(with_entry(content, "/d", 3), "/b\n/c\n/d\n");
+        assert_eq!(with_entry("", "/a", 3), "/a\n");
+    }
+}
@@ -0,0 +1,31 @@
+// ── ccs library ──
+//
+// Everything behind the `ccs` binary, which is a thin CLI over these modules
+// (main.rs). The supported surface for other tools is session-state
+// detection and the tmux queries it's built on:
+//
+//   let windows = ccs::tmux::list_windows("ccs")?;
+//   let states = ccs::StateDetector::new().detect(&windows);
+//
+// Detection reads the same config file and hook event files as ccs itself
+// (see config.rs and paths.rs). The remaining modules are public only so the
+// binary can reach them and may change without notice.
+
+pub mod config;
+pub mod paths;
+pub mod sidebar;
+pub mod tmux;
+
+#[doc(hidden)]
+pub mod cli;
+#[doc(hidden)]
+pub mod colors;
+#[doc(hidden)]
+pub mod commands;
+#[doc(hidden)]
+pub mod git;
+#[doc(hidden)]
+pub mod history;
+
+pub use sidebar::state::{StateDetector, WindowState};
+pub use tmux::WindowInfo;
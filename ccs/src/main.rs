@@ -1,28 +1,101 @@
-mod cli;
-mod colors;
-mod commands;
-mod sidebar;
-mod tmux;
-
+use ccs::cli::{Cli, Command};
+use ccs::{colors, commands, config, sidebar, tmux};
 use clap::Parser;
-use cli::{Cli, Command};
 
 fn main() {
     let cli = Cli::parse();
+    if cli.no_color {
+        colors::disable();
+    }
 
-    let result = match cli.command {
-        Command::Start { name, dir } => commands::start::run(&name, dir.as_deref()),
-        Command::List => commands::list::run(),
-        Command::Kill { name } => commands::kill::run(&name),
-        Command::AllKill => commands::kill::run_all(),
-        Command::Resume => commands::resume::run(),
-        Command::Sidebar => sidebar::app::run(),
-        Command::Hook { event } => commands::hook::run(event),
-        Command::Init => commands::init::run(),
-    };
-
-    if let Err(e) = result {
+    if let Err(e) = run(cli) {
         eprintln!("{e}");
         std::process::exit(1);
     }
 }
+
+fn run(cli: Cli) -> Result<(), String> {
+    // Only commands that talk to the tmux session resolve (and validate) it,
+    // so hooks and local housekeeping never fail on a bad `$CCS_SESSION`.
+    let session = move || tmux::resolve_session(cli.tmux_session);
+
+    match cli.command {
+        Command::Start {
+            name,
+            dir,
+            create_dir,
+            pick_dir,
+            here,
+            worktree,
+            select,
+            continue_conversation,
+            claude_args,
+        } => {
+            let claude_args = commands::start::claude_args(continue_conversation, claude_args);
+            let opts = commands::start::StartOptions {
+                dir: dir.as_deref(),
+                create_dir,
+                pick_dir,
+                here,
+                worktree: worktree.as_deref(),
+                select,
+                claude_args: &claude_args,
+            };
+            commands::start::run(&session()?, &name, &opts)
+        }
+        Command::List => commands::list::run(&session()?),
+        Command::Kill {
+            name,
+            remove_worktree,
+        } => commands::kill::run(&session()?, &name, remove_worktree),
+        Command::Swap { a, b } => commands::swap::run(&session()?, &a, &b),
+        Command::AllKill => commands::kill::run_all(&session()?),
+        Command::Next => commands::cycle::run(&session()?, true),
+        Command::Prev => commands::cycle::run(&session()?, false),
+        Command::Focus => commands::focus::run(&session()?),
+        Command::Count { state } => commands::count::run(&session()?, state.as_deref()),
+        Command::Info { name } => commands::info::run(&session()?, &name),
+        Command::Status {
+            format,
+            separator,
+            json,
+        } => commands::status::run(&session()?, &format, &separator, json),
+        Command::Send { name, text, enter } => {
+            commands::send::run(&session()?, &name, &text, enter)
+        }
+        Command::Broadcast { text, enter } => commands::send::broadcast(&session()?, &text, enter),
+        Command::Recent => commands::recent::run(),
+        Command::Resume { pick } => commands::resume::run(&session()?, pick),
+        Command::Sidebar => sidebar::app::run(&session()?),
+        Command::Hook { event } => commands::hook::run(event),
+        Command::Init {
+            dry_run,
+            force,
+            project,
+            path,
+            timeout,
+            no_async,
+        } => {
+            let dir = path.or(project.then(|| ".".to_string()));
+            let mut hooks = config::Config::load().hooks;
+            if let Some(timeout) = timeout {
+                hooks.timeout = timeout;
+            }
+            if no_async {
+                hooks.run_async = false;
+            }
+            // Explicit hook options re-merge, so they reach installed entries too
+            let force = force || timeout.is_some() || no_async;
+            commands::init::run(
+                dry_run,
+                force,
+                dir.as_deref().map(std::path::Path::new),
+                &hooks,
+            )
+        }
+        Command::Doctor { fix } => commands::doctor::run(fix),
+        Command::Man => commands::man::run(),
+        Command::Events { session, follow } => commands::events::run(session.as_deref(), follow),
+        Command::Prune { older_than } => commands::prune::run(older_than),
+    }
+}
@@ -0,0 +1,277 @@
+// ── Filesystem locations ──
+//
+// Where ccs keeps its data. Each location can be overridden with an env var so
+// tests and sandboxes get isolated paths instead of sharing the user's data.
+//
+//   CCS_EVENTS_DIR   hook event files   (default $XDG_STATE_HOME/ccs/events)
+//   config file                         (default $XDG_CONFIG_HOME/ccs/config.toml)
+//   sidebar log, with $CCS_DEBUG        (default $XDG_STATE_HOME/ccs/sidebar.log)
+//   start directory history             (default $XDG_STATE_HOME/ccs/dirs)
+//
+// Without the XDG variable, or when the legacy ~/.ccs/events or
+// ~/.ccs/config.toml already exists, the legacy location is used, so existing
+// setups keep working untouched. To migrate, move the files over and remove
+// the ~/.ccs copies:
+//
+//   mv ~/.ccs/events "$XDG_STATE_HOME/ccs/events"
+//   mv ~/.ccs/config.toml "$XDG_CONFIG_HOME/ccs/config.toml"
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+// ── Helpers ──
+
+/// Home directory from a `$HOME` value; unset and empty are both errors.
+fn home_from(value: Option<OsString>) -> Result<PathBuf, String> {
+    match value {
+        Some(v) if !v.is_empty() => Ok(PathBuf::from(v)),
+        _ => Err("$HOME is not set; ccs needs it to find ~/.ccs and ~/.claude".to_string()),
+    }
+}
+
+/// Use `value` when it's set and non-empty, otherwise the default.
+fn resolve(
+    value: Option<OsString>,
+    default: impl FnOnce() -> Result<PathBuf, String>,
+) -> Result<PathBuf, String> {
+    match value {
+        Some(v) if !v.is_empty() => Ok(PathBuf::from(v)),
+        _ => default(),
+    }
+}
+
+/// `~/.ccs/{name}` when it already exists, else `{xdg}/ccs/{name}` when the
+/// XDG base directory is set, else `~/.ccs/{name}`.
+fn xdg_or_legacy(
+    home: &Path,
+    xdg: Option<OsString>,
+    name: &str,
+    exists: impl Fn(&Path) -> bool,
+) -> PathBuf {
+    let legacy = home.join(".ccs").join(name);
+    match xdg {
+        Some(base) if !base.is_empty() && !exists(&legacy) => {
+            PathBuf::from(base).join("ccs").join(name)
+        }
+        _ => legacy,
+    }
+}
+
+/// `path` with a leading `home` shown as `~`.
+fn tilde_with(path: &str, home: &str) -> String {
+    match path.strip_prefix(home) {
+        Some(rest) if !home.is_empty() && (rest.is_empty() || rest.starts_with('/')) => {
+            format!("~{rest}")
+        }
+        _ => path.to_string(),
+    }
+}
+
+/// Expand a leading `~` and any `$VAR` / `${VAR}` in `path`. `lookup` resolves
+/// variable names; unset variables are left as written. Only `~` and `~/...`
+/// at the start are expanded, so `~user` and mid-path tildes pass through, and
+/// `~` itself stays literal when there's no home rather than becoming `/`.
+fn expand_with(path: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(path.len());
+    let home = lookup("HOME").filter(|h| !h.is_empty());
+    let rest = match (path.strip_prefix('~'), home) {
+        (Some(tail), Some(home)) if tail.is_empty() || tail.starts_with('/') => {
+            out.push_str(&home);
+            tail
+        }
+        _ => path,
+    };
+
+    let mut chars = rest.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if c != '$' {
+            out.push(c);
+            continue;
+        }
+        let braced = chars.peek().is_some_and(|&(_, c)| c == '{');
+        let start = if braced { i + 2 } else { i + 1 };
+        let len = rest[start..]
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len() - start);
+        let name = &rest[start..start + len];
+        let end = start + len + usize::from(braced);
+        let closed = !braced || rest[start + len..].starts_with('}');
+        let value = if name.is_empty() || !closed {
+            None
+        } else {
+            lookup(name)
+        };
+        match value {
+            Some(value) => {
+                out.push_str(&value);
+                while chars.peek().is_some_and(|&(j, _)| j < end) {
+                    chars.next();
+                }
+            }
+            None => out.push(c),
+        }
+    }
+    out
+}
+
+// ── Public API ──
+
+/// The user's home directory. Errors when `$HOME` is unset or empty instead
+/// of letting paths quietly resolve under `/`.
+pub fn home_dir() -> Result<PathBuf, String> {
+    home_from(std::env::var_os("HOME"))
+}
+
+/// Shorten `path` for display by writing the home directory as `~`.
+pub fn tilde(path: &str) -> String {
+    tilde_with(path, &std::env::var("HOME").unwrap_or_default())
+}
+
+/// Expand `~` and environment variables the way a shell would have, for paths
+/// that reach ccs unexpanded (e.g. quoted, or passed through a wrapper).
+pub fn expand(path: &str) -> String {
+    expand_with(path, |name| std::env::var(name).ok())
+}
+
+/// Directory holding one `{session_id}.jsonl` event file per Claude session.
+pub fn events_dir() -> Result<PathBuf, String> {
+    resolve(std::env::var_os("CCS_EVENTS_DIR"), || {
+        let xdg = std::env::var_os("XDG_STATE_HOME");
+        Ok(xdg_or_legacy(&home_dir()?, xdg, "events", Path::exists))
+    })
+}
+
+/// Where the sidebar appends diagnostics when `$CCS_DEBUG` is set.
+pub fn sidebar_log_path() -> Result<PathBuf, String> {
+    let xdg = std::env::var_os("XDG_STATE_HOME");
+    Ok(xdg_or_legacy(
+        &home_dir()?,
+        xdg,
+        "sidebar.log",
+        Path::exists,
+    ))
+}
+
+/// Directories `ccs start` has opened sessions in (see history.rs).
+pub fn history_path() -> Result<PathBuf, String> {
+    let xdg = std::env::var_os("XDG_STATE_HOME");
+    Ok(xdg_or_legacy(&home_dir()?, xdg, "dirs", Path::exists))
+}
+
+/// Optional user config file.
+pub fn config_path() -> Result<PathBuf, String> {
+    let xdg = std::env::var_os("XDG_CONFIG_HOME");
+    Ok(xdg_or_legacy(
+        &home_dir()?,
+        xdg,
+        "config.toml",
+        Path::exists,
+    ))
+}
+
+// ── Tests ──
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "HOME" => Some("/home/me".to_string()),
+            "PROJ" => Some("api".to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_expand_tilde() {
+        assert_eq!(expand_with("~", lookup), "/home/me");
+        assert_eq!(expand_with("~/sub", lookup), "/home/me/sub");
+    }
+
+    #[test]
+    fn test_expand_only_leading_tilde() {
+        assert_eq!(expand_with("~other/x", lookup), "~other/x");
+        assert_eq!(expand_with("/a/~/b", lookup), "/a/~/b");
+    }
+
+    #[test]
+    fn test_expand_env_vars() {
+        assert_eq!(expand_with("$HOME/sub", lookup), "/home/me/sub");
+        assert_eq!(expand_with("/code/${PROJ}-v2", lookup), "/code/api-v2");
+        assert_eq!(expand_with("/code/$NOPE/x", lookup), "/code/$NOPE/x");
+        assert_eq!(expand_with("/cost/$5", lookup), "/cost/$5");
+    }
+
+    #[test]
+    fn test_expand_tilde_without_home_stays_literal() {
+        assert_eq!(expand_with("~/sub", |_| None), "~/sub");
+        assert_eq!(expand_with("~/sub", |_| Some(String::new())), "~/sub");
+    }
+
+    #[test]
+    fn test_home_from_rejects_unset_and_empty() {
+        assert!(home_from(None).is_err());
+        assert!(home_from(Some(OsString::new())).is_err());
+        assert_eq!(
+            home_from(Some(OsString::from("/home/me"))),
+            Ok(PathBuf::from("/home/me"))
+        );
+    }
+
+    #[test]
+    fn test_tilde_only_replaces_leading_home() {
+        assert_eq!(tilde_with("/home/me/code", "/home/me"), "~/code");
+        assert_eq!(tilde_with("/home/me", "/home/me"), "~");
+        assert_eq!(tilde_with("/home/meg/code", "/home/me"), "/home/meg/code");
+        assert_eq!(tilde_with("/srv/home/me", "/home/me"), "/srv/home/me");
+        assert_eq!(tilde_with("/tmp", ""), "/tmp");
+    }
+
+    #[test]
+    fn test_xdg_used_when_set_and_no_legacy_dir() {
+        let home = Path::new("/home/me");
+        let xdg = || Some(OsString::from("/home/me/.local/state"));
+        assert_eq!(
+            xdg_or_legacy(home, xdg(), "events", |_| false),
+            PathBuf::from("/home/me/.local/state/ccs/events")
+        );
+        assert_eq!(
+            xdg_or_legacy(home, None, "events", |_| false),
+            PathBuf::from("/home/me/.ccs/events")
+        );
+        assert_eq!(
+            xdg_or_legacy(home, Some(OsString::new()), "events", |_| false),
+            PathBuf::from("/home/me/.ccs/events")
+        );
+    }
+
+    #[test]
+    fn test_existing_legacy_dir_wins_over_xdg() {
+        let home = Path::new("/home/me");
+        let xdg = Some(OsString::from("/home/me/.local/state"));
+        assert_eq!(
+            xdg_or_legacy(home, xdg, "events", |_| true),
+            PathBuf::from("/home/me/.ccs/events")
+        );
+    }
+
+    #[test]
+    fn test_resolve_override() {
+        let path = resolve(Some(OsString::from("/sandbox/events")), || {
+            Ok(PathBuf::from("/default"))
+        });
+        assert_eq!(path, Ok(PathBuf::from("/sandbox/events")));
+    }
+
+    #[test]
+    fn test_resolve_unset_or_empty_uses_default() {
+        let default = || Ok(PathBuf::from("/default"));
+        assert_eq!(resolve(None, default), Ok(PathBuf::from("/default")));
+        assert_eq!(
+            resolve(Some(OsString::new()), default),
+            Ok(PathBuf::from("/default"))
+        );
+        assert!(resolve(None, || home_from(None)).is_err());
+    }
+}
@@ -1,114 +1,311 @@
 // ── Sidebar application ──
 
 use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::io::{self, stdout};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 
 use crossterm::cursor;
+use crossterm::event::{
+    KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+};
 use crossterm::execute;
 use crossterm::terminal::{self, DisableLineWrap, EnableLineWrap};
 use ratatui::Terminal;
 use ratatui::backend::CrosstermBackend;
+use signal_hook::consts::{SIGHUP, SIGINT, SIGTERM};
 
-use crate::sidebar::event::{self, Action};
-use crate::sidebar::state::{StateDetector, WindowState};
-use crate::sidebar::ui::SidebarWidget;
+use crate::colors::Theme;
+use crate::config::{Config, SortOrder, StatusLabels};
+use crate::paths;
+use crate::sidebar::debug;
+use crate::sidebar::event::{self, Action, Keymap};
+use crate::sidebar::state::{self, StateDetector, WindowState};
+use crate::sidebar::ui::{self, Glyphs, SidebarWidget};
 use crate::tmux::{self, WindowInfo};
 
 // ── Types ──
 
 struct SidebarApp {
+    session: String,
     windows: Vec<WindowInfo>,
     states: HashMap<u32, WindowState>,
     selected: usize,
     tick: u64,
     detector: StateDetector,
+    show_help: bool,
+    /// Query typed after `/`; Some while filter mode is on.
+    filter: Option<String>,
+    theme: Theme,
+    glyphs: &'static Glyphs,
+    spinner: Vec<String>,
+    blink_asking: bool,
+    keys: Keymap,
+    sort: SortOrder,
+    pin_asking: bool,
+    group_by_dir: bool,
+    /// Leave Done windows out of the list (`sidebar.hide_done`).
+    hide_done: bool,
+    labels: StatusLabels,
+    /// Kill windows that have been Done this long (`sidebar.auto_close_done_after`).
+    auto_close: Option<Duration>,
+    /// This sidebar's own window id, resolved from `TMUX_PANE` at startup.
+    own_window: Option<String>,
+    /// Prefix `own_window`'s name with its state glyph (`sidebar.window_status`).
+    window_status: bool,
+    /// Config problem shown in the footer until the next key press.
+    warning: Option<String>,
+    /// Glyph currently prefixed to `own_window`'s name.
+    shown_glyph: Option<&'static str>,
 }
 
 // ── Constants ──
 
 const REFRESH_EVERY: u64 = 2;
 
+/// Ticks between full pane-capture rounds: about once a second. Panes whose
+/// hook state changed are captured sooner (see `StateDetector::capture_every`).
+const CAPTURE_EVERY: u64 = 10;
+
 // ── Public API ──
 
-pub fn run() -> Result<(), String> {
+pub fn run(session: &str) -> Result<(), String> {
     // No alternate screen — render in-place in tmux pane (matches bash behavior)
     let mut stdout = stdout();
     execute!(stdout, cursor::Hide, DisableLineWrap).map_err(|e| format!("terminal: {e}"))?;
     terminal::enable_raw_mode().map_err(|e| format!("terminal: {e}"))?;
+    // Ask for unambiguous key reports so ⌘ shortcuts arrive with their modifier.
+    // Terminals without the kitty keyboard protocol ignore the request.
+    execute!(
+        stdout,
+        PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES)
+    )
+    .ok();
 
-    let result = run_loop();
+    let result = run_loop(session);
 
     // Cleanup
+    execute!(stdout, PopKeyboardEnhancementFlags).ok();
     terminal::disable_raw_mode().ok();
     execute!(stdout, cursor::Show, EnableLineWrap).ok();
 
+    debug::log_error("sidebar exited", result.clone());
     result
 }
 
 // ── Helpers ──
 
-fn run_loop() -> Result<(), String> {
+fn run_loop(session: &str) -> Result<(), String> {
+    // tmux signals the pane on teardown; note it and leave through the normal
+    // cleanup instead of dying with the terminal still in raw mode
+    let stop = Arc::new(AtomicBool::new(false));
+    for signal in [SIGTERM, SIGINT, SIGHUP] {
+        signal_hook::flag::register(signal, Arc::clone(&stop))
+            .map_err(|e| format!("signal {signal}: {e}"))?;
+    }
+
     let backend = CrosstermBackend::new(io::stdout());
     let mut terminal = Terminal::new(backend).map_err(|e| format!("terminal: {e}"))?;
 
+    let config = Config::load();
+    let own_window = std::env::var("TMUX_PANE")
+        .ok()
+        .and_then(|pane| tmux::window_id_of(&pane).ok());
+
+    let (keys, warnings) = Keymap::from_config(&config.keys);
+    let glyphs = Glyphs::load();
     let mut app = SidebarApp {
+        session: session.to_string(),
         windows: Vec::new(),
         states: HashMap::new(),
         selected: 0,
         tick: 0,
-        detector: StateDetector::new(),
+        detector: StateDetector::new().capture_every(CAPTURE_EVERY),
+        show_help: false,
+        filter: None,
+        theme: Theme::load(),
+        glyphs,
+        spinner: ui::spinner_frames(config.sidebar.spinner.as_ref(), glyphs),
+        blink_asking: config.sidebar.blink_asking,
+        keys,
+        sort: config.sidebar.sort,
+        pin_asking: config.sidebar.pin_asking,
+        group_by_dir: config.sidebar.group_by_dir,
+        hide_done: config.sidebar.hide_done,
+        labels: config.sidebar.labels,
+        auto_close: config
+            .sidebar
+            .auto_close_done_after
+            .map(Duration::from_secs),
+        own_window,
+        window_status: config.sidebar.window_status,
+        warning: (!warnings.is_empty()).then(|| warnings.join("; ")),
+        shown_glyph: None,
     };
+    let mut last_drawn: Option<u64> = None;
 
     loop {
-        // Refresh window list periodically
-        if app.tick.is_multiple_of(REFRESH_EVERY) {
-            refresh_windows(&mut app);
+        if stop.load(Ordering::Relaxed) {
+            debug::log("signalled, exiting");
+            sync_window_name(&mut app, true);
+            return Ok(());
         }
 
-        // Detect states every tick
+        // Refresh window list periodically; exit with the session rather than
+        // keep rendering its last window list
+        if app.tick.is_multiple_of(REFRESH_EVERY) && !refresh_windows(&mut app) {
+            debug::log(&format!("session {} is gone, exiting", app.session));
+            return Ok(());
+        }
+
+        // Detect states every tick; only the sidebar on screen reads panes
+        let visible = app.own_window.is_none() || in_active_window(&app);
+        app.detector.set_visible(visible);
         app.states = app.detector.detect(&app.windows);
+        reorder_windows(&mut app);
+        close_done_windows(&mut app);
+        sync_window_name(&mut app, false);
 
-        // Render
-        terminal
-            .draw(|frame| {
-                let area = frame.area();
-                let widget = SidebarWidget {
-                    windows: &app.windows,
-                    states: &app.states,
-                    selected: app.selected,
-                    tick: app.tick,
-                };
-                frame.render_widget(widget, area);
-            })
-            .map_err(|e| format!("render: {e}"))?;
+        // Render only when something visible changed
+        let key = render_key(&app);
+        if last_drawn != Some(key) {
+            last_drawn = Some(key);
+            terminal
+                .draw(|frame| {
+                    let area = frame.area();
+                    // While filtering only matches render, with the top one highlighted
+                    let matches;
+                    let (windows, selected) = match &app.filter {
+                        Some(query) => {
+                            matches = filter_windows(shown(&app), query);
+                            (matches.as_slice(), 0)
+                        }
+                        None => (shown(&app), app.selected),
+                    };
+                    let widget = SidebarWidget {
+                        windows,
+                        states: &app.states,
+                        tools: app.detector.tools(),
+                        titles: app.detector.titles(),
+                        selected,
+                        elapsed: windows
+                            .get(selected)
+                            .and_then(|w| app.detector.in_state_for(w)),
+                        tick: app.tick,
+                        spinner: &app.spinner,
+                        blink_asking: app.blink_asking,
+                        group_by_dir: app.group_by_dir,
+                        labels: &app.labels,
+                        show_help: app.show_help,
+                        filter: app.filter.as_deref(),
+                        theme: &app.theme,
+                        glyphs: app.glyphs,
+                        keys: &app.keys,
+                        warning: app.warning.as_deref(),
+                    };
+                    frame.render_widget(widget, area);
+                })
+                .map_err(|e| format!("render: {e}"))?;
+        }
 
         // Handle events
-        let actions = event::poll();
+        let actions = event::poll(app.filter.is_some(), &app.keys);
+        let half_page = terminal
+            .size()
+            .map(|size| usize::from(size.height.saturating_sub(2) / 2))
+            .unwrap_or(1)
+            .max(1);
         let mut moved = false;
 
         for action in actions {
+            if !matches!(action, Action::Resize | Action::Tick) {
+                app.warning = None;
+            }
+            // While help is open, only the close keys (and housekeeping) do anything
+            if app.show_help
+                && !matches!(
+                    action,
+                    Action::Help | Action::Cancel | Action::Resize | Action::Tick
+                )
+            {
+                continue;
+            }
+
             match action {
-                Action::Up => {
-                    if app.selected > 0 {
-                        app.selected -= 1;
-                        moved = true;
-                    }
-                }
-                Action::Down => {
-                    if app.selected + 1 < app.windows.len() {
-                        app.selected += 1;
+                Action::Up
+                | Action::Down
+                | Action::HalfPageUp
+                | Action::HalfPageDown
+                | Action::First
+                | Action::Last => {
+                    let row = nav_target(&action, app.selected, shown(&app).len(), half_page);
+                    if row != app.selected {
+                        app.selected = row;
                         moved = true;
                     }
                 }
                 Action::Select => {
-                    if let Some(win) = app.windows.get(app.selected) {
-                        let _ = tmux::select_window(win.index);
+                    let target = match app.filter.take() {
+                        Some(query) => filter_windows(shown(&app), &query).into_iter().next(),
+                        None => shown(&app).get(app.selected).cloned(),
+                    };
+                    if let Some(win) = target {
+                        // A Done pane gets Claude back instead of a view of the shell
+                        if app.states.get(&win.index) == Some(&WindowState::Done)
+                            && debug::ok(
+                                &format!("respawn-pane {}", win.pane_id),
+                                tmux::respawn_claude(&win.pane_id),
+                            )
+                            && let Ok(dir) = paths::events_dir()
+                        {
+                            state::clear_pane_events(&dir, &win.pane_id);
+                        }
+                        debug::log_error(
+                            &format!("select-window {}", win.window_id),
+                            tmux::select_window(&win.window_id),
+                        );
                         refresh_windows(&mut app);
                         app.tick = 0;
                         continue;
                     }
                 }
-                Action::Quit => return Ok(()),
+                Action::Quit => {
+                    sync_window_name(&mut app, true);
+                    return Ok(());
+                }
+                Action::FocusClaude => {
+                    debug::log_error("select claude pane", tmux::select_claude_pane());
+                }
+                Action::FocusTerminal => {
+                    debug::log_error("select terminal pane", tmux::select_terminal_pane());
+                }
+                Action::Detach => {
+                    debug::log_error("detach-client", tmux::detach_client());
+                }
+                Action::Help => app.show_help = !app.show_help,
+                Action::Cancel => {
+                    app.show_help = false;
+                    app.filter = None;
+                }
+                Action::Filter => app.filter = Some(String::new()),
+                Action::FilterPush(c) => {
+                    if let Some(query) = &mut app.filter {
+                        query.push(c);
+                    }
+                }
+                Action::FilterPop => {
+                    if let Some(query) = &mut app.filter {
+                        query.pop();
+                    }
+                }
+                Action::Resize => {
+                    // We draw in place (no alternate screen), so wipe leftovers
+                    // beyond the new bounds before the next full redraw
+                    terminal.clear().map_err(|e| format!("terminal: {e}"))?;
+                    last_drawn = None;
+                }
                 Action::Tick => {}
             }
         }
@@ -116,7 +313,10 @@ fn run_loop() -> Result<(), String> {
         // Single tmux call after all queued keys are processed
         if moved {
             if let Some(win) = app.windows.get(app.selected) {
-                let _ = tmux::select_window_sidebar(win.index);
+                debug::log_error(
+                    &format!("select-window {}", win.window_id),
+                    tmux::select_window_sidebar(&win.window_id),
+                );
             }
             // Skip next refresh so select-window has time to take effect
             app.tick = 1;
@@ -126,8 +326,232 @@ fn run_loop() -> Result<(), String> {
     }
 }
 
-fn refresh_windows(app: &mut SidebarApp) {
-    if let Ok(windows) = tmux::list_windows() {
+/// Fingerprint of everything the sidebar renders. While any session is Working
+/// (or Asking, with `blink_asking`) the tick is included so it keeps animating.
+fn render_key(app: &SidebarApp) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for win in &app.windows {
+        win.index.hash(&mut hasher);
+        win.name.hash(&mut hasher);
+        win.is_active.hash(&mut hasher);
+        win.pane_path.hash(&mut hasher);
+        app.states.get(&win.index).hash(&mut hasher);
+        app.detector.tools().get(&win.index).hash(&mut hasher);
+        app.detector.titles().get(&win.index).hash(&mut hasher);
+    }
+    app.selected.hash(&mut hasher);
+    // The footer's elapsed time ticks over once a second
+    app.windows
+        .get(app.selected)
+        .and_then(|w| app.detector.in_state_for(w))
+        .map(|d| d.as_secs())
+        .hash(&mut hasher);
+    app.show_help.hash(&mut hasher);
+    app.filter.hash(&mut hasher);
+    app.warning.hash(&mut hasher);
+
+    let animating = app
+        .states
+        .values()
+        .any(|s| *s == WindowState::Working || (app.blink_asking && *s == WindowState::Asking));
+    if animating {
+        app.tick.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// Window-name glyph for a state; None restores the plain name.
+fn name_glyph(state: WindowState) -> Option<&'static str> {
+    match state {
+        WindowState::Working | WindowState::Compacting => Some(tmux::WORKING_GLYPH),
+        WindowState::Asking => Some(tmux::ASKING_GLYPH),
+        _ => None,
+    }
+}
+
+/// Prefix this sidebar's window name with its state glyph, renaming only when
+/// the glyph changes. `restore` drops the glyph (on quit).
+fn sync_window_name(app: &mut SidebarApp, restore: bool) {
+    if !app.window_status {
+        return;
+    }
+    let Some(id) = &app.own_window else {
+        return;
+    };
+    let Some(win) = app.windows.iter().find(|w| w.window_id == *id) else {
+        return;
+    };
+    let glyph = if restore {
+        None
+    } else {
+        app.states.get(&win.index).copied().and_then(name_glyph)
+    };
+    if glyph != app.shown_glyph
+        && debug::ok(
+            &format!("rename-window {id}"),
+            tmux::set_window_status_glyph(id, &win.name, glyph),
+        )
+    {
+        app.shown_glyph = glyph;
+    }
+}
+
+/// Windows whose name contains `query`, ignoring case, in display order.
+fn filter_windows(windows: &[WindowInfo], query: &str) -> Vec<WindowInfo> {
+    let query = query.to_lowercase();
+    windows
+        .iter()
+        .filter(|w| w.name.to_lowercase().contains(&query))
+        .cloned()
+        .collect()
+}
+
+/// Row a navigation action moves the selection to, clamped to `len` rows.
+fn nav_target(action: &Action, selected: usize, len: usize, half_page: usize) -> usize {
+    let row = match action {
+        Action::Up => selected.saturating_sub(1),
+        Action::Down => selected + 1,
+        Action::HalfPageUp => selected.saturating_sub(half_page),
+        Action::HalfPageDown => selected + half_page,
+        Action::First => 0,
+        Action::Last => len,
+        _ => selected,
+    };
+    row.min(len.saturating_sub(1))
+}
+
+/// Rank for `sidebar.sort = "state"`: sessions needing attention first.
+fn state_rank(state: WindowState) -> u8 {
+    match state {
+        WindowState::Asking => 0,
+        WindowState::Working | WindowState::Compacting => 1,
+        WindowState::Idle => 2,
+        WindowState::Limited => 3,
+        WindowState::Fresh => 4,
+        WindowState::Done => 5,
+    }
+}
+
+/// Sort windows for display. Ties fall back to the tmux index so the order
+/// is stable from one tick to the next.
+fn sort_windows(windows: &mut [WindowInfo], states: &HashMap<u32, WindowState>, order: SortOrder) {
+    match order {
+        SortOrder::Index => windows.sort_by_key(|w| w.index),
+        SortOrder::Name => windows.sort_by(|a, b| a.name.cmp(&b.name).then(a.index.cmp(&b.index))),
+        SortOrder::State => windows.sort_by_key(|w| {
+            let state = states.get(&w.index).copied().unwrap_or(WindowState::Fresh);
+            (state_rank(state), w.index)
+        }),
+    }
+}
+
+/// Move Asking windows to the front. A stable sort on a boolean key is a
+/// stable partition, so both groups keep their relative order.
+fn pin_asking(windows: &mut [WindowInfo], states: &HashMap<u32, WindowState>) {
+    windows.sort_by_key(|w| states.get(&w.index) != Some(&WindowState::Asking));
+}
+
+/// Gather windows sharing a directory next to the first of them, so each
+/// directory gets one header. Groups keep the order their first window had.
+fn group_by_dir(windows: &mut [WindowInfo]) {
+    let mut first_seen: Vec<String> = Vec::new();
+    for win in windows.iter() {
+        if !first_seen.contains(&win.pane_path) {
+            first_seen.push(win.pane_path.clone());
+        }
+    }
+    windows.sort_by_key(|w| first_seen.iter().position(|p| *p == w.pane_path));
+}
+
+/// Move Done windows to the back, keeping both groups in order, so the shown
+/// windows are a prefix of the list and rows still index `app.windows`.
+fn sink_done(windows: &mut [WindowInfo], states: &HashMap<u32, WindowState>) {
+    windows.sort_by_key(|w| states.get(&w.index) == Some(&WindowState::Done));
+}
+
+/// The windows the list shows: all of them, or with `hide_done` only those
+/// `sink_done` left in front. The active window may be hidden, leaving the
+/// selection past the end with no row highlighted.
+fn shown(app: &SidebarApp) -> &[WindowInfo] {
+    if !app.hide_done {
+        return &app.windows;
+    }
+    let end = app
+        .windows
+        .iter()
+        .position(|w| app.states.get(&w.index) == Some(&WindowState::Done))
+        .unwrap_or(app.windows.len());
+    &app.windows[..end]
+}
+
+/// Apply the configured order to `app.windows`, keeping the selection on the
+/// same window rather than the same row.
+fn reorder_windows(app: &mut SidebarApp) {
+    let selected = app.windows.get(app.selected).map(|w| w.window_id.clone());
+    sort_windows(&mut app.windows, &app.states, app.sort);
+    if app.pin_asking {
+        pin_asking(&mut app.windows, &app.states);
+    }
+    if app.group_by_dir {
+        group_by_dir(&mut app.windows);
+    }
+    if app.hide_done {
+        sink_done(&mut app.windows, &app.states);
+    }
+    if let Some(id) = selected
+        && let Some(pos) = app.windows.iter().position(|w| w.window_id == id)
+    {
+        app.selected = pos;
+    }
+}
+
+/// Whether this sidebar sits in the session's active window. Every window
+/// runs a sidebar; this is the one on screen.
+fn in_active_window(app: &SidebarApp) -> bool {
+    app.windows
+        .iter()
+        .any(|w| w.is_active && app.own_window.as_deref() == Some(w.window_id.as_str()))
+}
+
+/// Kill windows that have sat Done past `auto_close`. The selected window is
+/// never closed, so nothing disappears while the user is looking at it.
+/// Only the sidebar in the active window closes anything, so they don't all
+/// race to kill the same windows.
+fn close_done_windows(app: &mut SidebarApp) {
+    let Some(limit) = app.auto_close else {
+        return;
+    };
+    if !in_active_window(app) {
+        return;
+    }
+    let selected = app.windows.get(app.selected).map(|w| w.index);
+    let mut closed = false;
+    for win in &app.windows {
+        if Some(win.index) != selected
+            && app.detector.done_for(win).is_some_and(|done| done >= limit)
+        {
+            closed |= debug::ok(
+                &format!("kill-window {}", win.window_id),
+                tmux::kill_window_id(&win.window_id),
+            );
+        }
+    }
+    if closed {
+        refresh_windows(app);
+    }
+}
+
+/// Reload the window list. Returns false once the session itself is gone;
+/// a failed listing while it still exists keeps the previous list.
+fn refresh_windows(app: &mut SidebarApp) -> bool {
+    let windows = tmux::list_windows(&app.session);
+    if let Err(e) = &windows {
+        debug::log(&format!("list-windows {}: {e}", app.session));
+    }
+    if !windows.as_ref().is_ok_and(|w| !w.is_empty()) && !tmux::has_session(&app.session) {
+        return false;
+    }
+    if let Ok(windows) = windows {
         // Sync selected to the tmux-active window
         let active_pos = windows.iter().position(|w| w.is_active).unwrap_or(0);
 
@@ -139,4 +563,109 @@ fn refresh_windows(app: &mut SidebarApp) {
             app.selected = app.windows.len() - 1;
         }
     }
+    true
+}
+
+// ── Tests ──
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tmux::test_window as window;
+
+    fn indices(windows: &[WindowInfo]) -> Vec<u32> {
+        windows.iter().map(|w| w.index).collect()
+    }
+
+    #[test]
+    fn test_filter_windows_ignores_case() {
+        let windows = vec![window(1, "web"), window(2, "API"), window(3, "rapid")];
+        assert_eq!(indices(&filter_windows(&windows, "api")), vec![2, 3]);
+        assert_eq!(indices(&filter_windows(&windows, "")), vec![1, 2, 3]);
+        assert!(filter_windows(&windows, "xyz").is_empty());
+    }
+
+    #[test]
+    fn test_nav_target_clamps() {
+        assert_eq!(nav_target(&Action::Up, 0, 5, 2), 0);
+        assert_eq!(nav_target(&Action::Down, 4, 5, 2), 4);
+        assert_eq!(nav_target(&Action::HalfPageDown, 1, 5, 2), 3);
+        assert_eq!(nav_target(&Action::HalfPageDown, 4, 5, 2), 4);
+        assert_eq!(nav_target(&Action::HalfPageUp, 1, 5, 2), 0);
+        assert_eq!(nav_target(&Action::First, 3, 5, 2), 0);
+        assert_eq!(nav_target(&Action::Last, 0, 5, 2), 4);
+        assert_eq!(nav_target(&Action::Last, 0, 0, 2), 0);
+    }
+
+    #[test]
+    fn test_sort_by_state_puts_asking_first() {
+        let mut windows = vec![
+            window(1, "a"),
+            window(2, "b"),
+            window(3, "c"),
+            window(4, "d"),
+        ];
+        let states = HashMap::from([
+            (1, WindowState::Done),
+            (2, WindowState::Idle),
+            (3, WindowState::Asking),
+            (4, WindowState::Working),
+        ]);
+        sort_windows(&mut windows, &states, SortOrder::State);
+        assert_eq!(indices(&windows), vec![3, 4, 2, 1]);
+
+        sort_windows(&mut windows, &states, SortOrder::Index);
+        assert_eq!(indices(&windows), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_pin_asking_keeps_relative_order() {
+        let mut windows = vec![
+            window(1, "d"),
+            window(2, "c"),
+            window(3, "b"),
+            window(4, "a"),
+        ];
+        let states = HashMap::from([(2, WindowState::Asking), (4, WindowState::Asking)]);
+        sort_windows(&mut windows, &states, SortOrder::Name);
+        pin_asking(&mut windows, &states);
+        assert_eq!(indices(&windows), vec![4, 2, 3, 1]);
+    }
+
+    #[test]
+    fn test_group_by_dir_gathers_after_first() {
+        let at = |index, dir: &str| WindowInfo {
+            pane_path: dir.to_string(),
+            ..window(index, "w")
+        };
+        let mut windows = vec![
+            at(1, "/a"),
+            at(2, "/b"),
+            at(3, "/a"),
+            at(4, "/c"),
+            at(5, "/b"),
+        ];
+        group_by_dir(&mut windows);
+        assert_eq!(indices(&windows), vec![1, 3, 2, 5, 4]);
+    }
+
+    #[test]
+    fn test_sink_done_keeps_order() {
+        let mut windows = vec![
+            window(1, "a"),
+            window(2, "b"),
+            window(3, "c"),
+            window(4, "d"),
+        ];
+        let states = HashMap::from([(1, WindowState::Done), (3, WindowState::Done)]);
+        sink_done(&mut windows, &states);
+        assert_eq!(indices(&windows), vec![2, 4, 1, 3]);
+    }
+
+    #[test]
+    fn test_sort_by_name_ties_on_index() {
+        let mut windows = vec![window(1, "web"), window(2, "api"), window(3, "api")];
+        sort_windows(&mut windows, &HashMap::new(), SortOrder::Name);
+        assert_eq!(indices(&windows), vec![2, 3, 1]);
+    }
 }
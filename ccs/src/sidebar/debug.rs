@@ -0,0 +1,119 @@
+// ── Debug logging ──
+//
+// Opt-in diagnostics for the sidebar. With $CCS_DEBUG set, tmux failures the
+// sidebar would otherwise ignore, and the error it exits with, are appended
+// to sidebar.log next to the events dir (~/.ccs/sidebar.log by default), and
+// every state detection is traced as JSONL to detect.jsonl beside it.
+// Unset, every call returns after one cached flag check.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use serde_json::Value;
+
+use crate::paths;
+
+// ── Constants ──
+
+/// Past this size a log is rotated to `sidebar.log.old` (or `detect.jsonl.old`),
+/// keeping at most two files' worth of history.
+const MAX_LOG_BYTES: u64 = 256 * 1024;
+
+static ENABLED: LazyLock<bool> =
+    LazyLock::new(|| std::env::var("CCS_DEBUG").is_ok_and(|v| !v.is_empty() && v != "0"));
+
+// ── Helpers ──
+
+/// Append `line` to the log at `path`, rotating it first once it's too big.
+fn append(path: &Path, line: &str, max_bytes: u64) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    if fs::metadata(path).is_ok_and(|m| m.len() > max_bytes) {
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        fs::rename(path, path.with_file_name(format!("{file_name}.old")))?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{line}")
+}
+
+// ── Public API ──
+
+/// Whether `$CCS_DEBUG` turned logging on.
+pub fn enabled() -> bool {
+    *ENABLED
+}
+
+/// Append a timestamped line to the sidebar log. Failures to log are ignored.
+pub fn log(msg: &str) {
+    if !enabled() {
+        return;
+    }
+    let Ok(path) = paths::sidebar_log_path() else {
+        return;
+    };
+    let ts = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let _ = append(&path, &format!("{ts} {msg}"), MAX_LOG_BYTES);
+}
+
+/// Append one JSONL record to detect.jsonl, beside the sidebar log.
+pub fn trace(record: &Value) {
+    if !enabled() {
+        return;
+    }
+    let Ok(path) = paths::sidebar_log_path() else {
+        return;
+    };
+    let _ = append(
+        &path.with_file_name("detect.jsonl"),
+        &record.to_string(),
+        MAX_LOG_BYTES,
+    );
+}
+
+/// Log `result`'s error, if any, under `context` (e.g. "select-window @3").
+pub fn log_error<T>(context: &str, result: Result<T, String>) {
+    if let Err(e) = result {
+        log(&format!("{context}: {e}"));
+    }
+}
+
+/// Like `log_error`, for call sites that branch on success.
+pub fn ok<T>(context: &str, result: Result<T, String>) -> bool {
+    let ok = result.is_ok();
+    log_error(context, result);
+    ok
+}
+
+// ── Tests ──
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_rotates_large_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sidebar.log");
+
+        append(&path, "first", 8).unwrap();
+        append(&path, "second", 8).unwrap();
+        append(&path, "third", 8).unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "third\n");
+        let old = fs::read_to_string(dir.path().join("sidebar.log.old")).unwrap();
+        assert_eq!(old, "first\nsecond\n");
+
+        // The trace keeps its own extension in the rotated name
+        let trace = dir.path().join("detect.jsonl");
+        append(&trace, "{}", 1).unwrap();
+        append(&trace, "{}", 1).unwrap();
+        assert!(dir.path().join("detect.jsonl.old").exists());
+        assert!(!dir.path().join("detect.log.old").exists());
+    }
+}
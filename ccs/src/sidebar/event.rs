@@ -4,38 +4,129 @@ use std::time::Duration;
 
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
 
+use crate::config::KeysConfig;
+
 // ── Types ──
 
+#[derive(Clone, Copy)]
 pub enum Action {
     Up,
     Down,
+    /// Ctrl-u / Ctrl-d — move half a screen of sessions.
+    HalfPageUp,
+    HalfPageDown,
+    /// g / G — jump to the first or last session.
+    First,
+    Last,
     Select,
     Quit,
+    /// Toggle the keybinding help overlay.
+    Help,
+    /// ⌘ + j — focus this window's claude pane.
+    FocusClaude,
+    /// ⌘ + m — focus this window's terminal pane.
+    FocusTerminal,
+    /// ⌘ + ; — detach from tmux.
+    Detach,
+    /// `/` — start typing a filter query.
+    Filter,
+    /// A character typed while filtering.
+    FilterPush(char),
+    /// Backspace while filtering.
+    FilterPop,
+    /// Esc — closes whatever overlay is open.
+    Cancel,
+    /// Terminal was resized — clear and redraw at the new size.
+    Resize,
     Tick,
 }
 
+/// A key as written in the `[keys]` config: a key code, optionally with Ctrl.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct KeySpec {
+    pub code: KeyCode,
+    pub ctrl: bool,
+}
+
+/// Sidebar keys for the remappable actions.
+pub struct Keymap {
+    pub up: Vec<KeySpec>,
+    pub down: Vec<KeySpec>,
+    pub select: Vec<KeySpec>,
+    pub quit: Vec<KeySpec>,
+    pub help: Vec<KeySpec>,
+}
+
 // ── Public API ──
 
+impl Default for Keymap {
+    fn default() -> Self {
+        let key = |code| KeySpec { code, ctrl: false };
+        Self {
+            up: vec![key(KeyCode::Up), key(KeyCode::Char('k'))],
+            down: vec![key(KeyCode::Down), key(KeyCode::Char('j'))],
+            select: vec![key(KeyCode::Enter)],
+            quit: vec![key(KeyCode::Char('q'))],
+            help: vec![key(KeyCode::Char('?'))],
+        }
+    }
+}
+
+impl Keymap {
+    /// Build the keymap from config. An action with an invalid spec keeps its
+    /// default keys, and a warning naming it is returned for the sidebar's
+    /// footer (stderr would be drawn over).
+    pub fn from_config(config: &KeysConfig) -> (Keymap, Vec<String>) {
+        let defaults = Keymap::default();
+        let mut warnings = Vec::new();
+        let mut pick = |name: &str, specs: &[String], default: Vec<KeySpec>| {
+            if specs.is_empty() {
+                return default;
+            }
+            match specs.iter().map(|s| parse_key(s).ok_or(s)).collect() {
+                Ok(keys) => keys,
+                Err(bad) => {
+                    warnings.push(format!("invalid key '{bad}' for keys.{name}"));
+                    default
+                }
+            }
+        };
+        let keys = Keymap {
+            up: pick("up", &config.up, defaults.up),
+            down: pick("down", &config.down, defaults.down),
+            select: pick("select", &config.select, defaults.select),
+            quit: pick("quit", &config.quit, defaults.quit),
+            help: pick("help", &config.help, defaults.help),
+        };
+        (keys, warnings)
+    }
+}
+
 /// Poll for input events with a 100ms timeout. Returns accumulated actions.
 /// Batches rapid arrow presses into single moves (key draining).
-pub fn poll() -> Vec<Action> {
+///
+/// `filtering` says whether filter mode is on. It's tracked across the batch,
+/// so "/api" typed quickly routes the letters to the query, not to navigation.
+pub fn poll(mut filtering: bool, keys: &Keymap) -> Vec<Action> {
     let mut actions = Vec::new();
 
     if event::poll(Duration::from_millis(100)).unwrap_or(false) {
-        // Process first event
-        if let Ok(Event::Key(key)) = event::read()
-            && let Some(action) = key_to_action(key)
-        {
-            actions.push(action);
-        }
-
-        // Drain queued keys (batch rapid arrow presses)
-        while event::poll(Duration::from_millis(0)).unwrap_or(false) {
-            if let Ok(Event::Key(key)) = event::read()
-                && let Some(action) = key_to_action(key)
-            {
-                actions.push(action);
+        // Process first event, then drain queued keys (batch rapid arrow presses)
+        let mut wait = true;
+        while wait || event::poll(Duration::from_millis(0)).unwrap_or(false) {
+            wait = false;
+            let Ok(ev) = event::read() else {
+                continue;
+            };
+            let Some(action) = event_to_action(ev, filtering, keys) else {
+                continue;
+            };
+            match action {
+                Action::Filter => filtering = true,
+                Action::Select | Action::Cancel => filtering = false,
+                _ => {}
             }
+            actions.push(action);
         }
     }
 
@@ -48,18 +139,178 @@ pub fn poll() -> Vec<Action> {
 
 // ── Helpers ──
 
-fn key_to_action(key: KeyEvent) -> Option<Action> {
+fn event_to_action(ev: Event, filtering: bool, keys: &Keymap) -> Option<Action> {
+    match ev {
+        Event::Key(key) if filtering => filter_key_to_action(key),
+        Event::Key(key) => key_to_action(key, keys),
+        Event::Resize(_, _) => Some(Action::Resize),
+        _ => None,
+    }
+}
+
+/// Parse a key spec like "k", "enter" or "ctrl-n".
+fn parse_key(spec: &str) -> Option<KeySpec> {
+    let lower = spec.to_ascii_lowercase();
+    let (ctrl, name) = match lower
+        .strip_prefix("ctrl-")
+        .or_else(|| lower.strip_prefix("c-"))
+    {
+        Some(rest) => (true, &spec[spec.len() - rest.len()..]),
+        None => (false, spec),
+    };
+    let code = match name.to_ascii_lowercase().as_str() {
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "enter" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "space" => KeyCode::Char(' '),
+        "backspace" => KeyCode::Backspace,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        _ => {
+            let mut chars = name.chars();
+            match (chars.next(), chars.next()) {
+                // Terminals report Ctrl-letter in lowercase
+                (Some(c), None) if ctrl => KeyCode::Char(c.to_ascii_lowercase()),
+                (Some(c), None) => KeyCode::Char(c),
+                _ => return None,
+            }
+        }
+    };
+    Some(KeySpec { code, ctrl })
+}
+
+fn key_to_action(key: KeyEvent, keys: &Keymap) -> Option<Action> {
     // Only handle key press events (ignore release/repeat)
     if key.kind != crossterm::event::KeyEventKind::Press {
         return None;
     }
 
+    // The tmux ⌘ shortcuts, for terminals that report Cmd/Super to the app.
+    // ⌘ + p (sessions) focuses the sidebar, which already has focus here.
+    if key.modifiers.contains(KeyModifiers::SUPER) {
+        return match key.code {
+            KeyCode::Char('j') => Some(Action::FocusClaude),
+            KeyCode::Char('m') => Some(Action::FocusTerminal),
+            KeyCode::Char(';') => Some(Action::Detach),
+            _ => None,
+        };
+    }
+
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    let pressed = KeySpec {
+        code: key.code,
+        ctrl,
+    };
+    let bound = [
+        (&keys.up, Action::Up),
+        (&keys.down, Action::Down),
+        (&keys.select, Action::Select),
+        (&keys.quit, Action::Quit),
+        (&keys.help, Action::Help),
+    ];
+    if let Some((_, action)) = bound.iter().find(|(specs, _)| specs.contains(&pressed)) {
+        return Some(*action);
+    }
+
+    match key.code {
+        KeyCode::Char('c') if ctrl => Some(Action::Quit),
+        KeyCode::Char('u') if ctrl => Some(Action::HalfPageUp),
+        KeyCode::Char('d') if ctrl => Some(Action::HalfPageDown),
+        KeyCode::Char('g') | KeyCode::Home => Some(Action::First),
+        KeyCode::Char('G') | KeyCode::End => Some(Action::Last),
+        KeyCode::Char('/') => Some(Action::Filter),
+        KeyCode::Esc => Some(Action::Cancel),
+        _ => None,
+    }
+}
+
+/// Keys while filtering: printable characters edit the query.
+fn filter_key_to_action(key: KeyEvent) -> Option<Action> {
+    if key.kind != crossterm::event::KeyEventKind::Press {
+        return None;
+    }
+
     match key.code {
-        KeyCode::Up | KeyCode::Char('k') => Some(Action::Up),
-        KeyCode::Down | KeyCode::Char('j') => Some(Action::Down),
-        KeyCode::Enter => Some(Action::Select),
-        KeyCode::Char('q') => Some(Action::Quit),
         KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Some(Action::Quit),
+        KeyCode::Char(c) => Some(Action::FilterPush(c)),
+        KeyCode::Backspace => Some(Action::FilterPop),
+        KeyCode::Enter => Some(Action::Select),
+        KeyCode::Esc => Some(Action::Cancel),
         _ => None,
     }
 }
+
+// ── Tests ──
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_parse_key_specs() {
+        let key = |code, ctrl| Some(KeySpec { code, ctrl });
+        assert_eq!(parse_key("k"), key(KeyCode::Char('k'), false));
+        assert_eq!(parse_key("G"), key(KeyCode::Char('G'), false));
+        assert_eq!(parse_key("Enter"), key(KeyCode::Enter, false));
+        assert_eq!(parse_key("ctrl-N"), key(KeyCode::Char('n'), true));
+        assert_eq!(parse_key("c-p"), key(KeyCode::Char('p'), true));
+        assert_eq!(parse_key("ctrl-"), None);
+        assert_eq!(parse_key("hyper-x"), None);
+    }
+
+    #[test]
+    fn test_invalid_spec_keeps_default() {
+        let config = KeysConfig {
+            up: vec!["ctrl-p".to_string()],
+            down: vec!["ctrl-n".to_string(), "bogus".to_string()],
+            ..KeysConfig::default()
+        };
+        let (keys, warnings) = Keymap::from_config(&config);
+        assert_eq!(warnings, ["invalid key 'bogus' for keys.down"]);
+        assert_eq!(
+            keys.up,
+            vec![KeySpec {
+                code: KeyCode::Char('p'),
+                ctrl: true
+            }]
+        );
+        assert_eq!(keys.down, Keymap::default().down);
+    }
+
+    #[test]
+    fn test_cmd_shortcuts_map_to_pane_actions() {
+        let keys = Keymap::default();
+        let cmd = |c| key_to_action(press(KeyCode::Char(c), KeyModifiers::SUPER), &keys);
+        assert!(matches!(cmd('j'), Some(Action::FocusClaude)));
+        assert!(matches!(cmd('m'), Some(Action::FocusTerminal)));
+        assert!(matches!(cmd(';'), Some(Action::Detach)));
+        assert!(cmd('p').is_none());
+        // Plain j is still navigation
+        let j = key_to_action(press(KeyCode::Char('j'), KeyModifiers::NONE), &keys);
+        assert!(matches!(j, Some(Action::Down)));
+    }
+
+    #[test]
+    fn test_configured_keys_replace_defaults() {
+        let config = KeysConfig {
+            up: vec!["ctrl-p".to_string()],
+            ..KeysConfig::default()
+        };
+        let (keys, _) = Keymap::from_config(&config);
+        let up = key_to_action(press(KeyCode::Char('p'), KeyModifiers::CONTROL), &keys);
+        assert!(matches!(up, Some(Action::Up)));
+        assert!(key_to_action(press(KeyCode::Char('k'), KeyModifiers::NONE), &keys).is_none());
+        let quit = key_to_action(press(KeyCode::Char('c'), KeyModifiers::CONTROL), &keys);
+        assert!(matches!(quit, Some(Action::Quit)));
+    }
+}
@@ -1,4 +1,5 @@
 pub mod app;
+pub mod debug;
 pub mod event;
 pub mod state;
 pub mod ui;
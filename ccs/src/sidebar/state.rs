@@ -1,23 +1,35 @@
 // ── State detection for Claude session windows ──
 //
 // Reads CCS event files written by Claude Code hooks to determine sidebar state.
-// Each Claude session has an event file at ~/.ccs/events/{session_id}.jsonl.
+// Each Claude session has an event file at ~/.ccs/events/{session_id}.jsonl
+// (or $XDG_STATE_HOME/ccs/events, or $CCS_EVENTS_DIR; see paths.rs).
 // The sidebar matches events to tmux windows by comparing the event's `pane_id`
 // (from $TMUX_PANE) to each window's tmux pane ID. This correctly handles
 // multiple sessions in the same working directory.
+//
+// Some states never fire a hook (e.g. Claude stopping at a usage limit looks
+// like an ordinary Stop, and the folder-trust and permission menus block without
+// any event). For those, the last few lines of the Claude pane are matched
+// against known messages.
 
 use std::collections::HashMap;
 use std::fs;
 use std::io::{BufRead, Seek, SeekFrom};
-use std::path::{Path, PathBuf};
+use std::path::Path;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
 
-use serde::Deserialize;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
 
+use crate::config::{Config, DetectConfig};
+use crate::paths;
+use crate::sidebar::debug;
 use crate::tmux;
 
 // ── Types ──
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum WindowState {
     /// New session, no hook events fired yet.
     Fresh,
@@ -25,29 +37,256 @@ pub enum WindowState {
     Working,
     /// Claude is waiting for user to answer a question.
     Asking,
+    /// Claude stopped at an API usage or rate limit.
+    Limited,
     /// Claude finished answering — waiting for next user message.
     Idle,
-    /// Claude process exited — shell prompt visible.
+    /// Claude process exited — a shell or other program has the pane.
     Done,
+    /// Claude is summarizing the conversation to free up context. Read off
+    /// the pane only; no hook reports it.
+    Compacting,
+}
+
+/// The outcome of refining a pane's recorded state with a capture, reused
+/// until the next capture.
+struct Refined {
+    recorded: WindowState,
+    state: WindowState,
+    tool: Option<String>,
 }
 
-#[derive(Deserialize)]
-struct EventEntry {
-    state: String,
-    #[allow(dead_code)]
-    cwd: String,
+/// One line of an event file, as written by `ccs hook`. Serialized with
+/// serde on both ends, so any cwd or title round-trips intact.
+#[derive(Serialize, Deserialize)]
+pub struct EventEntry {
+    pub state: String,
+    pub cwd: String,
     /// Tmux pane ID (e.g. "%0") — used to match events to windows.
     #[serde(default)]
-    pane_id: String,
-    #[allow(dead_code)]
-    ts: u64,
+    pub pane_id: String,
+    pub ts: u64,
+    /// The user's most recent prompt, trimmed to one line by the hook.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub title: String,
 }
 
+// ── Constants ──
+
+/// How many trailing non-blank pane lines the limit check looks at.
+const TAIL_LINES: usize = 4;
+
+/// Menus put several options plus a footer under the `❯` marker, so the
+/// question check looks a little further up.
+const QUESTION_TAIL_LINES: usize = 8;
+
+/// Blocking prompts that don't go through AskUserQuestion: the folder-trust
+/// dialog, permission/plan approvals, and any numbered menu with the `❯` cursor.
+/// Numbered menus laid out without the `❯` cursor are only trusted this close
+/// to the bottom; longer tails would catch ordinary numbered lists.
+const MENU_TAIL_LINES: usize = 3;
+
+/// One option marker of a `1) foo  2) bar` style menu.
+static MENU_OPTION_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:^|\s)\d[.)]\s+\S").expect("valid regex"));
+
+/// Tool calls render as `⏺ Bash(cargo test)`; some progress lines read
+/// `Running Bash…` instead. The tool list is deliberately short.
+static TOOL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?:⏺\s*|Running\s+)(Bash|Read|Edit|MultiEdit|Write|Update|Grep|Glob|WebFetch|WebSearch|Task)\b",
+    )
+    .expect("valid regex")
+});
+
+/// How far up the tool-call line may sit above the spinner and input box.
+const TOOL_TAIL_LINES: usize = 12;
+
+/// Captured lines are cut to this many chars. Every pattern matches near the
+/// start of a line, and a pane full of minified output shouldn't be copied
+/// wholesale each tick.
+const CAPTURE_LINE_CHARS: usize = 256;
+
+static QUESTION_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)do you trust the files|yes, proceed|❯\s*\d+[.)]\s").expect("valid regex")
+});
+
+/// Login steps that block until the user acts: the login method picker, the
+/// browser sign-in with its paste-code box, and the errors that send you
+/// back to `/login`. Bare "log in" or "authentication" would also match
+/// ordinary output about auth code, so only the prompt wording counts.
+static AUTH_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)select login method|paste code here|open this url|use the url below to sign in|please run /login|authentication (failed|required)|invalid api key",
+    )
+    .expect("valid regex")
+});
+
+/// The footer Claude shows under the spinner for as long as it's generating.
+static INTERRUPT_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)esc to interrupt").expect("valid regex"));
+
+/// The spinner line shown while context is being compacted, whether by
+/// `/compact` or automatically.
+static COMPACT_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)compacting conversation").expect("valid regex"));
+
+static LIMIT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)usage limit|rate limit|try again (later|at)").expect("valid regex")
+});
+
 // ── Helpers ──
 
-fn events_dir() -> PathBuf {
-    let home = std::env::var("HOME").unwrap_or_default();
-    PathBuf::from(home).join(".ccs").join("events")
+/// The last `n` non-blank lines of captured pane content, oldest first.
+fn tail_lines(content: &str, n: usize) -> Vec<&str> {
+    let mut lines: Vec<&str> = content
+        .lines()
+        .map(str::trim_end)
+        .filter(|l| !l.trim().is_empty())
+        .rev()
+        .take(n)
+        .collect();
+    lines.reverse();
+    lines
+}
+
+/// Whether the pane tail shows Claude stopped at a usage/rate limit.
+fn detect_limit(content: &str) -> bool {
+    tail_lines(content, TAIL_LINES)
+        .iter()
+        .any(|line| LIMIT_RE.is_match(line))
+}
+
+/// Whether the pane tail shows a menu waiting for the user's choice.
+fn detect_question(content: &str) -> bool {
+    tail_lines(content, QUESTION_TAIL_LINES)
+        .iter()
+        .any(|line| QUESTION_RE.is_match(line))
+}
+
+/// Whether the pane tail shows Claude waiting on a login step. The sign-in
+/// URL wraps across several lines, so this looks as far up as menus do.
+fn detect_auth(content: &str) -> bool {
+    tail_lines(content, QUESTION_TAIL_LINES)
+        .iter()
+        .any(|line| AUTH_RE.is_match(line))
+}
+
+/// Whether the pane tail shows the "esc to interrupt" footer, i.e. Claude is
+/// generating right now, whatever the last hook said.
+fn detect_interrupt_footer(content: &str) -> bool {
+    tail_lines(content, TAIL_LINES)
+        .iter()
+        .any(|line| INTERRUPT_RE.is_match(line))
+}
+
+/// Whether the pane tail shows the compaction spinner. Only the tail counts:
+/// a "Compacting conversation" line left in the scrollback is long over.
+fn detect_compacting(content: &str) -> bool {
+    tail_lines(content, TAIL_LINES)
+        .iter()
+        .any(|line| COMPACT_RE.is_match(line))
+}
+
+/// Whether the last few lines offer two or more numbered options.
+fn detect_numbered_menu(content: &str) -> bool {
+    let options: usize = tail_lines(content, MENU_TAIL_LINES)
+        .iter()
+        .map(|line| MENU_OPTION_RE.find_iter(line).count())
+        .sum();
+    options >= 2
+}
+
+/// The most recent tool call in the pane tail, if any.
+fn detect_tool(content: &str) -> Option<String> {
+    tail_lines(content, TOOL_TAIL_LINES)
+        .iter()
+        .rev()
+        .find_map(|line| TOOL_RE.captures(line))
+        .map(|caps| caps[1].to_string())
+}
+
+/// Whether a window's hook-derived state needs the pane contents to refine it.
+fn needs_capture(state: WindowState) -> bool {
+    matches!(
+        state,
+        WindowState::Fresh | WindowState::Working | WindowState::Idle
+    )
+}
+
+/// Whether to capture a pane this `detect`: on a full capture round, or when
+/// there's no refinement yet for its recorded state. Either way only states
+/// the pane contents can refine are worth a tmux call, and only while the
+/// detector is `visible`.
+fn should_capture(
+    recorded: WindowState,
+    last: Option<&Refined>,
+    capture_all: bool,
+    visible: bool,
+) -> bool {
+    let stale = last.is_none_or(|r| r.recorded != recorded);
+    visible && needs_capture(recorded) && (capture_all || stale)
+}
+
+/// The last `lines` non-blank lines of a capture, each cut to
+/// `CAPTURE_LINE_CHARS`. The checks above only ever read the tail.
+fn trim_capture(content: &str, lines: usize) -> String {
+    tail_lines(content, lines)
+        .iter()
+        .map(|line| match line.char_indices().nth(CAPTURE_LINE_CHARS) {
+            Some((end, _)) => &line[..end],
+            None => line,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Refine a hook-derived state with the pane contents. A limit stop is recorded
+/// as idle, as is a turn that ended by offering numbered choices; trust and
+/// permission menus block before or during a turn, and a login step at any
+/// point. The compaction spinner takes precedence over the interrupt footer
+/// it also carries, which means a turn is underway even if its hook hasn't
+/// landed; once either is gone, the recorded state stands again.
+fn refine_state(state: WindowState, content: &str) -> WindowState {
+    match state {
+        WindowState::Idle if detect_limit(content) => WindowState::Limited,
+        WindowState::Idle if detect_numbered_menu(content) => WindowState::Asking,
+        WindowState::Fresh | WindowState::Working if detect_question(content) => {
+            WindowState::Asking
+        }
+        WindowState::Fresh | WindowState::Working | WindowState::Idle if detect_auth(content) => {
+            WindowState::Asking
+        }
+        WindowState::Fresh | WindowState::Working | WindowState::Idle
+            if detect_compacting(content) =>
+        {
+            WindowState::Compacting
+        }
+        WindowState::Fresh | WindowState::Idle if detect_interrupt_footer(content) => {
+            WindowState::Working
+        }
+        _ => state,
+    }
+}
+
+/// Refine `recorded` with a capture, holding back an Asking read off the pane
+/// until the previous detect saw it too: a frame caught mid-render can show a
+/// `❯` for a moment. `prompted_before` is None for a window this detector
+/// hasn't seen, which has no earlier frame to confirm against, so one-shot
+/// callers like `ccs status` still commit straight away. Also returns whether
+/// this capture showed a prompt, for the next call.
+fn settle(
+    recorded: WindowState,
+    content: Option<&str>,
+    prompted_before: Option<bool>,
+) -> (WindowState, bool) {
+    let refined = content.map_or(recorded, |c| refine_state(recorded, c));
+    let prompted = refined == WindowState::Asking && recorded != WindowState::Asking;
+    if prompted && prompted_before == Some(false) {
+        (recorded, prompted)
+    } else {
+        (refined, prompted)
+    }
 }
 
 /// Read the last line of a file efficiently.
@@ -59,7 +298,7 @@ fn read_last_line(path: &Path) -> Option<String> {
         return None;
     }
 
-    // Read last 1KB — event lines are ~80 bytes, so this is more than enough
+    // Read last 1KB — event lines are ~80 bytes plus a short title, so this is more than enough
     let tail_start = len.saturating_sub(1024);
     let mut reader = std::io::BufReader::new(file);
     reader.seek(SeekFrom::Start(tail_start)).ok()?;
@@ -89,35 +328,101 @@ fn read_last_line(path: &Path) -> Option<String> {
     last
 }
 
-/// Load the latest event from each event file in the events directory.
-/// Returns a vec of (pane_id, state) for matching against windows.
-fn load_latest_events(dir: &Path) -> Vec<(String, String)> {
+/// Pane ID recorded by the last event in an event file, if any.
+pub fn last_pane_id(path: &Path) -> Option<String> {
+    let line = read_last_line(path)?;
+    let event = serde_json::from_str::<EventEntry>(&line).ok()?;
+    (!event.pane_id.is_empty()).then_some(event.pane_id)
+}
+
+/// Remove the event files whose latest event came from `pane_id`, so a pane
+/// that gets a new Claude process starts out Fresh. Returns how many went.
+pub fn clear_pane_events(dir: &Path, pane_id: &str) -> usize {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("jsonl"))
+        .filter(|path| last_pane_id(path).as_deref() == Some(pane_id))
+        .filter(|path| fs::remove_file(path).is_ok())
+        .count()
+}
+
+/// Load the latest event for each pane across all event files in the events directory.
+/// Returns one entry per pane for matching against windows.
+///
+/// A pane can appear in several files (e.g. Claude restarted in the same pane with a new
+/// session id), so the newest event by timestamp wins. On a timestamp tie an `asking`
+/// event takes precedence — the user must answer it before anything else can happen.
+pub fn load_latest_events(dir: &Path) -> Vec<EventEntry> {
     let entries = match fs::read_dir(dir) {
         Ok(e) => e,
         Err(_) => return Vec::new(),
     };
 
-    let mut results = Vec::new();
+    let mut latest: HashMap<String, EventEntry> = HashMap::new();
     for entry in entries.flatten() {
         let path = entry.path();
         if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
             continue;
         }
-        if let Some(line) = read_last_line(&path) {
-            if let Ok(event) = serde_json::from_str::<EventEntry>(&line) {
-                if !event.pane_id.is_empty() {
-                    results.push((event.pane_id, event.state));
-                }
-            }
+        let Some(line) = read_last_line(&path) else {
+            continue;
+        };
+        let Ok(event) = serde_json::from_str::<EventEntry>(&line) else {
+            continue;
+        };
+        if event.pane_id.is_empty() {
+            continue;
+        }
+
+        let newer = match latest.get(&event.pane_id) {
+            Some(prev) => supersedes(&event, prev),
+            None => true,
+        };
+        if newer {
+            latest.insert(event.pane_id.clone(), event);
         }
     }
 
-    results
+    latest.into_values().collect()
+}
+
+/// Whether `event` should replace `prev` as the latest event for a pane.
+fn supersedes(event: &EventEntry, prev: &EventEntry) -> bool {
+    if event.ts != prev.ts {
+        return event.ts > prev.ts;
+    }
+    event.state == "asking" && prev.state != "asking"
 }
 
+/// Restart the clock for panes whose state changed and drop panes that went
+/// away; panes still in the same state keep their start time.
+fn track_since(
+    since: &mut HashMap<String, (WindowState, Instant)>,
+    states: &HashMap<String, WindowState>,
+    now: Instant,
+) {
+    since.retain(|pane, _| states.contains_key(pane));
+    for (pane, state) in states {
+        match since.get(pane) {
+            Some((seen, _)) if seen == state => {}
+            _ => {
+                since.insert(pane.clone(), (*state, now));
+            }
+        }
+    }
+}
+
+/// Map a recorded hook state to a window state.
+///
+/// `asking` is written by the PreToolUse(AskUserQuestion) hook and holds until the
+/// PostToolUse hook records `ask-done` (or a newer `working`/`idle` event arrives).
 fn state_from_str(s: &str) -> WindowState {
     match s {
-        "working" => WindowState::Working,
+        "working" | "ask-done" => WindowState::Working,
         "asking" => WindowState::Asking,
         "idle" => WindowState::Idle,
         _ => WindowState::Fresh,
@@ -126,58 +431,246 @@ fn state_from_str(s: &str) -> WindowState {
 
 // ── Public API ──
 
-pub struct StateDetector;
+impl WindowState {
+    /// Lowercase name, as printed by `ccs status` and accepted by `ccs count --state`.
+    pub fn name(self) -> &'static str {
+        match self {
+            WindowState::Fresh => "fresh",
+            WindowState::Working => "working",
+            WindowState::Asking => "asking",
+            WindowState::Limited => "limited",
+            WindowState::Idle => "idle",
+            WindowState::Done => "done",
+            WindowState::Compacting => "compacting",
+        }
+    }
+}
+
+/// Whether a pane's foreground command means Claude has exited: one of
+/// `detect.done_commands`, or anything that isn't a claude-like command. The
+/// native installer's process reports its version ("2.0.14") as its name, so
+/// version-shaped commands count as claude-like too. An empty
+/// `detect.claude_commands` turns the second check off.
+pub fn claude_exited(command: &str, detect: &DetectConfig) -> bool {
+    let listed = |names: &[String]| names.iter().any(|n| n == command);
+    if listed(&detect.done_commands) {
+        return true;
+    }
+    let version = !command.is_empty()
+        && command.contains('.')
+        && command.chars().all(|c| c.is_ascii_digit() || c == '.');
+    !detect.claude_commands.is_empty() && !listed(&detect.claude_commands) && !version
+}
+
+/// Detects window states from hook events and pane contents. What it
+/// remembers between calls is keyed by claude pane id, which survives
+/// `renumber_on_kill` and window moves; results are keyed by window index,
+/// valid for the window list just passed in.
+pub struct StateDetector {
+    tools: HashMap<u32, String>,
+    titles: HashMap<u32, String>,
+    /// Each pane's state and when it entered it, reset on every change.
+    since: HashMap<String, (WindowState, Instant)>,
+    /// Whether each pane's last capture showed a prompt (see `settle`).
+    prompted: HashMap<String, bool>,
+    /// State at the previous `detect`, kept only while tracing for `$CCS_DEBUG`.
+    traced: HashMap<String, WindowState>,
+    /// Last refinement per pane, reused on calls that don't capture.
+    refined: HashMap<String, Refined>,
+    /// Capture panes on every this-many `detect` calls (see `capture_every`).
+    capture_every: u64,
+    /// `detect` calls so far.
+    ticks: u64,
+    /// Whether anyone is looking at the results (see `set_visible`).
+    visible: bool,
+    /// `[detect]` settings: capture size and the command sets.
+    config: DetectConfig,
+}
+
+impl Default for StateDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl StateDetector {
+    /// A detector with no history, using the `[detect]` settings from the config file.
     pub fn new() -> Self {
-        Self
+        Self {
+            tools: HashMap::new(),
+            titles: HashMap::new(),
+            since: HashMap::new(),
+            prompted: HashMap::new(),
+            traced: HashMap::new(),
+            refined: HashMap::new(),
+            capture_every: 1,
+            ticks: 0,
+            visible: true,
+            config: Config::load().detect,
+        }
+    }
+
+    /// Capture panes only on every `ticks`-th `detect`, or when a pane's
+    /// recorded state changed since its last capture. Other calls reuse the
+    /// pane's last refinement. Each capture is a tmux process, and every
+    /// window runs its own sidebar, so a detector called each tick would
+    /// otherwise spawn windows² captures per tick.
+    pub fn capture_every(mut self, ticks: u64) -> Self {
+        self.capture_every = ticks.max(1);
+        self
+    }
+
+    /// Skip pane captures while hidden and go by the hook states alone. A
+    /// sidebar in a window nobody is looking at still renames its window, but
+    /// its refinements would only be thrown away.
+    pub fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    /// Tool each Working window was running at the last `detect`, by window index.
+    pub fn tools(&self) -> &HashMap<u32, String> {
+        &self.tools
+    }
+
+    /// Last prompt recorded for each window at the last `detect`, by window index.
+    pub fn titles(&self) -> &HashMap<u32, String> {
+        &self.titles
+    }
+
+    /// Record one window's detection inputs and outcome for `$CCS_DEBUG`.
+    /// `recorded` is the hook state matched to the pane; `captured` says
+    /// whether the pane contents were read to refine it.
+    fn trace(
+        &mut self,
+        win: &tmux::WindowInfo,
+        recorded: Option<&str>,
+        captured: bool,
+        state: WindowState,
+    ) {
+        if !debug::enabled() {
+            return;
+        }
+        let prev = self.traced.insert(win.pane_id.clone(), state);
+        let ts = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        debug::trace(&serde_json::json!({
+            "ts": ts,
+            "index": win.index,
+            "pane_id": win.pane_id,
+            "command": win.command,
+            "recorded": recorded,
+            "captured": captured,
+            "prev_state": prev.map(|s| format!("{s:?}")),
+            "new_state": format!("{state:?}"),
+            "changed": prev.is_some_and(|p| p != state),
+            "tool": self.tools.get(&win.index),
+        }));
+    }
+
+    /// How long a window has been in its current state, as of the last `detect`.
+    pub fn in_state_for(&self, win: &tmux::WindowInfo) -> Option<Duration> {
+        self.since.get(&win.pane_id).map(|(_, at)| at.elapsed())
+    }
+
+    /// How long a window has been Done without interruption, as of the last `detect`.
+    pub fn done_for(&self, win: &tmux::WindowInfo) -> Option<Duration> {
+        self.since
+            .get(&win.pane_id)
+            .filter(|(state, _)| *state == WindowState::Done)
+            .map(|(_, at)| at.elapsed())
     }
 
     /// Detect the state of each window. Returns a map from window_index to state.
     pub fn detect(&mut self, windows: &[tmux::WindowInfo]) -> HashMap<u32, WindowState> {
         let mut states = HashMap::new();
-
-        // Get foreground commands + pane IDs for all panes in one tmux call
-        let pane_infos: Vec<tmux::PaneInfo> =
-            tmux::list_pane_commands().unwrap_or_default();
-        let pane_cmds: HashMap<u32, &str> = pane_infos
-            .iter()
-            .map(|p| (p.window_index, p.command.as_str()))
-            .collect();
-        let pane_ids: HashMap<u32, &str> = pane_infos
-            .iter()
-            .map(|p| (p.window_index, p.pane_id.as_str()))
-            .collect();
+        self.tools.clear();
+        self.titles.clear();
 
         // Load all latest events once per detect cycle
-        let events = load_latest_events(&events_dir());
+        let events = paths::events_dir()
+            .map(|dir| load_latest_events(&dir))
+            .unwrap_or_default();
 
-        for win in windows {
-            let cmd = pane_cmds
-                .get(&win.index)
-                .copied()
-                .unwrap_or("zsh");
+        let capture_all = self.ticks.is_multiple_of(self.capture_every);
+        self.ticks += 1;
+        let mut prompted_now = HashMap::new();
+        let mut refined_now = HashMap::new();
+        let mut by_pane = HashMap::new();
 
-            // Shell prompt means Claude exited
-            if cmd == "zsh" || cmd == "bash" || cmd == "fish" {
+        for win in windows {
+            // A dead pane, or a shell or other non-claude program, means Claude exited
+            if win.dead || claude_exited(&win.command, &self.config) {
                 states.insert(win.index, WindowState::Done);
+                by_pane.insert(win.pane_id.clone(), WindowState::Done);
+                self.trace(win, None, false, WindowState::Done);
                 continue;
             }
 
             // Match event by pane_id — each tmux pane has a unique ID like "%0"
-            let win_pane_id = pane_ids.get(&win.index).copied().unwrap_or("");
-            let matched = events
-                .iter()
-                .find(|(pane_id, _)| pane_id == win_pane_id);
-
-            let state = match matched {
-                Some((_, state_str)) => state_from_str(state_str),
+            let matched = events.iter().find(|e| e.pane_id == win.pane_id);
+            let recorded = match matched {
+                Some(event) => state_from_str(&event.state),
                 None => WindowState::Fresh,
             };
+            if let Some(event) = matched.filter(|e| !e.title.is_empty()) {
+                self.titles.insert(win.index, event.title.clone());
+            }
+
+            let content = should_capture(
+                recorded,
+                self.refined.get(&win.pane_id),
+                capture_all,
+                self.visible,
+            )
+            .then(|| tmux::capture_pane(&win.pane_id).ok())
+            .flatten()
+            .map(|c| trim_capture(&c, self.config.capture_lines));
+            let content = content.as_deref();
+            let prompted_before = self.prompted.get(&win.pane_id).copied();
+            let (state, tool) = match (content, self.refined.remove(&win.pane_id)) {
+                // Not captured this time: the last capture still stands
+                (None, Some(last)) if last.recorded == recorded && needs_capture(recorded) => {
+                    if let Some(prompted) = prompted_before {
+                        prompted_now.insert(win.pane_id.clone(), prompted);
+                    }
+                    (last.state, last.tool)
+                }
+                _ => {
+                    let (state, prompted) = settle(recorded, content, prompted_before);
+                    prompted_now.insert(win.pane_id.clone(), prompted);
+                    let tool = content
+                        .filter(|_| state == WindowState::Working)
+                        .and_then(detect_tool);
+                    (state, tool)
+                }
+            };
+            if let Some(tool) = &tool {
+                self.tools.insert(win.index, tool.clone());
+            }
+            refined_now.insert(
+                win.pane_id.clone(),
+                Refined {
+                    recorded,
+                    state,
+                    tool,
+                },
+            );
 
             states.insert(win.index, state);
+            by_pane.insert(win.pane_id.clone(), state);
+            self.trace(
+                win,
+                matched.map(|e| e.state.as_str()),
+                content.is_some(),
+                state,
+            );
         }
 
+        self.prompted = prompted_now;
+        self.refined = refined_now;
+        track_since(&mut self.since, &by_pane, Instant::now());
         states
     }
 }
@@ -232,20 +725,32 @@ mod tests {
         let dir = tempfile::tempdir().unwrap();
 
         let mut f1 = fs::File::create(dir.path().join("session-a.jsonl")).unwrap();
-        writeln!(f1, r#"{{"state":"working","cwd":"/project-a","pane_id":"%0","ts":1000}}"#).unwrap();
-        writeln!(f1, r#"{{"state":"idle","cwd":"/project-a","pane_id":"%0","ts":1001}}"#).unwrap();
+        writeln!(
+            f1,
+            r#"{{"state":"working","cwd":"/project-a","pane_id":"%0","ts":1000}}"#
+        )
+        .unwrap();
+        writeln!(
+            f1,
+            r#"{{"state":"idle","cwd":"/project-a","pane_id":"%0","ts":1001}}"#
+        )
+        .unwrap();
 
         let mut f2 = fs::File::create(dir.path().join("session-b.jsonl")).unwrap();
-        writeln!(f2, r#"{{"state":"asking","cwd":"/project-b","pane_id":"%3","ts":2000}}"#).unwrap();
+        writeln!(
+            f2,
+            r#"{{"state":"asking","cwd":"/project-b","pane_id":"%3","ts":2000}}"#
+        )
+        .unwrap();
 
         let events = load_latest_events(dir.path());
         assert_eq!(events.len(), 2);
 
-        let a = events.iter().find(|(pane_id, _)| pane_id == "%0").unwrap();
-        assert_eq!(a.1, "idle");
+        let a = events.iter().find(|e| e.pane_id == "%0").unwrap();
+        assert_eq!(a.state, "idle");
 
-        let b = events.iter().find(|(pane_id, _)| pane_id == "%3").unwrap();
-        assert_eq!(b.1, "asking");
+        let b = events.iter().find(|e| e.pane_id == "%3").unwrap();
+        assert_eq!(b.state, "asking");
     }
 
     #[test]
@@ -254,20 +759,57 @@ mod tests {
 
         // Two sessions in the same cwd but different panes
         let mut f1 = fs::File::create(dir.path().join("session-a.jsonl")).unwrap();
-        writeln!(f1, r#"{{"state":"working","cwd":"/same/dir","pane_id":"%0","ts":1000}}"#).unwrap();
+        writeln!(
+            f1,
+            r#"{{"state":"working","cwd":"/same/dir","pane_id":"%0","ts":1000}}"#
+        )
+        .unwrap();
 
         let mut f2 = fs::File::create(dir.path().join("session-b.jsonl")).unwrap();
-        writeln!(f2, r#"{{"state":"idle","cwd":"/same/dir","pane_id":"%3","ts":1000}}"#).unwrap();
+        writeln!(
+            f2,
+            r#"{{"state":"idle","cwd":"/same/dir","pane_id":"%3","ts":1000}}"#
+        )
+        .unwrap();
 
         let events = load_latest_events(dir.path());
         assert_eq!(events.len(), 2);
 
         // Each should match to its own pane, not cross-contaminate
-        let a = events.iter().find(|(pane_id, _)| pane_id == "%0").unwrap();
-        assert_eq!(a.1, "working");
+        let a = events.iter().find(|e| e.pane_id == "%0").unwrap();
+        assert_eq!(a.state, "working");
+
+        let b = events.iter().find(|e| e.pane_id == "%3").unwrap();
+        assert_eq!(b.state, "idle");
+    }
+
+    #[test]
+    fn test_load_latest_events_reads_title() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let mut f = fs::File::create(dir.path().join("session.jsonl")).unwrap();
+        writeln!(
+            f,
+            r#"{{"state":"working","cwd":"/p","pane_id":"%0","ts":1000,"title":"fix the \"login\" bug"}}"#
+        )
+        .unwrap();
 
-        let b = events.iter().find(|(pane_id, _)| pane_id == "%3").unwrap();
-        assert_eq!(b.1, "idle");
+        let events = load_latest_events(dir.path());
+        assert_eq!(events[0].title, r#"fix the "login" bug"#);
+    }
+
+    #[test]
+    fn test_clear_pane_events_only_touches_that_pane() {
+        let dir = tempfile::tempdir().unwrap();
+        let event =
+            |pane: &str| format!(r#"{{"state":"idle","cwd":"/p","pane_id":"{pane}","ts":1}}"#);
+        fs::write(dir.path().join("a.jsonl"), event("%1") + "\n").unwrap();
+        fs::write(dir.path().join("b.jsonl"), event("%2") + "\n").unwrap();
+
+        assert_eq!(clear_pane_events(dir.path(), "%1"), 1);
+        let events = load_latest_events(dir.path());
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].pane_id, "%2");
     }
 
     #[test]
@@ -295,9 +837,448 @@ mod tests {
         assert!(events.is_empty());
     }
 
+    #[test]
+    fn test_same_pane_newest_event_wins() {
+        let dir = tempfile::tempdir().unwrap();
+
+        // Claude restarted in pane %0 — the old session's file still says asking
+        let mut old = fs::File::create(dir.path().join("old-session.jsonl")).unwrap();
+        writeln!(
+            old,
+            r#"{{"state":"asking","cwd":"/p","pane_id":"%0","ts":1000}}"#
+        )
+        .unwrap();
+
+        let mut new = fs::File::create(dir.path().join("new-session.jsonl")).unwrap();
+        writeln!(
+            new,
+            r#"{{"state":"idle","cwd":"/p","pane_id":"%0","ts":2000}}"#
+        )
+        .unwrap();
+
+        let events = load_latest_events(dir.path());
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].state, "idle");
+    }
+
+    #[test]
+    fn test_same_pane_ask_wins_tie() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let mut a = fs::File::create(dir.path().join("session-a.jsonl")).unwrap();
+        writeln!(
+            a,
+            r#"{{"state":"working","cwd":"/p","pane_id":"%0","ts":1000}}"#
+        )
+        .unwrap();
+
+        let mut b = fs::File::create(dir.path().join("session-b.jsonl")).unwrap();
+        writeln!(
+            b,
+            r#"{{"state":"asking","cwd":"/p","pane_id":"%0","ts":1000}}"#
+        )
+        .unwrap();
+
+        let events = load_latest_events(dir.path());
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].state, "asking");
+    }
+
+    #[test]
+    fn test_ask_done_clears_asking() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let mut f = fs::File::create(dir.path().join("session.jsonl")).unwrap();
+        writeln!(
+            f,
+            r#"{{"state":"asking","cwd":"/p","pane_id":"%0","ts":1000}}"#
+        )
+        .unwrap();
+        writeln!(
+            f,
+            r#"{{"state":"ask-done","cwd":"/p","pane_id":"%0","ts":1000}}"#
+        )
+        .unwrap();
+
+        let events = load_latest_events(dir.path());
+        assert_eq!(state_from_str(&events[0].state), WindowState::Working);
+    }
+
+    #[test]
+    fn test_detect_limit_messages() {
+        let claude = "\
+⏺ Refactoring the parser now.
+
+  ⎿  Claude usage limit reached. Your limit will reset at 3pm (America/New_York).
+
+> ";
+        assert!(detect_limit(claude));
+        assert!(detect_limit("API Error: 429 rate limit exceeded\n"));
+        assert!(detect_limit("Overloaded. Please try again later.\n"));
+        assert!(detect_limit("Limit hit, try again at 14:00\n"));
+    }
+
+    #[test]
+    fn test_detect_limit_only_checks_tail() {
+        let content = "\
+We should add a rate limit to the API.
+one
+two
+three
+four
+> ";
+        assert!(!detect_limit(content));
+        assert!(!detect_limit("Done! All tests pass.\n\n> \n"));
+    }
+
+    #[test]
+    fn test_detect_question_trust_prompt() {
+        let content = "\
+╭──────────────────────────────────────────────────────────╮
+│ Do you trust the files in this folder?                   │
+│                                                          │
+│ /Users/me/code/app                                       │
+│                                                          │
+│ ❯ 1. Yes, proceed                                        │
+│   2. No, exit                                            │
+╰──────────────────────────────────────────────────────────╯
+   Enter to confirm · Esc to exit
+";
+        assert!(detect_question(content));
+        assert_eq!(
+            refine_state(WindowState::Fresh, content),
+            WindowState::Asking
+        );
+    }
+
+    #[test]
+    fn test_detect_question_numbered_menu() {
+        let content = "\
+ Bash command
+   rm -rf target
+ Do you want to proceed?
+ ❯ 1. Yes
+   2. Yes, and don't ask again for rm commands
+   3. No, and tell Claude what to do differently (esc)
+";
+        assert!(detect_question(content));
+        assert_eq!(
+            refine_state(WindowState::Working, content),
+            WindowState::Asking
+        );
+    }
+
+    #[test]
+    fn test_detect_question_ignores_earlier_prose() {
+        let content = "\
+⏺ I don't trust the files in this folder to be formatted, so:
+  1. ran cargo fmt
+  2. ran clippy
+a
+b
+c
+d
+e
+f
+g
+> ";
+        assert!(!detect_question(content));
+        assert_eq!(refine_state(WindowState::Idle, content), WindowState::Idle);
+    }
+
+    #[test]
+    fn test_detect_numbered_menu_inline() {
+        let content = "\
+⏺ Which approach should I take?
+  1) patch the parser  2) rewrite the lexer  3) leave it
+";
+        assert!(detect_numbered_menu(content));
+        assert_eq!(
+            refine_state(WindowState::Idle, content),
+            WindowState::Asking
+        );
+    }
+
+    #[test]
+    fn test_detect_numbered_menu_one_per_line() {
+        assert!(detect_numbered_menu("Pick one:\n1. sqlite\n2. postgres\n"));
+    }
+
+    #[test]
+    fn test_detect_numbered_menu_ignores_lists_further_up() {
+        let content = "\
+Changes:
+1. renamed the module
+2. fixed the tests
+All done.
+Let me know if you need anything else.
+> ";
+        assert!(!detect_numbered_menu(content));
+        assert!(!detect_numbered_menu("Step 1. is done\n"));
+        assert_eq!(refine_state(WindowState::Idle, content), WindowState::Idle);
+    }
+
+    #[test]
+    fn test_interrupt_footer_means_working() {
+        let content = "\
+⏺ Reading the config loader
+
+✻ Pondering… (12s · ↓ 340 tokens · ESC to interrupt)
+
+> ";
+        assert!(detect_interrupt_footer(content));
+        assert_eq!(
+            refine_state(WindowState::Idle, content),
+            WindowState::Working
+        );
+        assert_eq!(
+            refine_state(WindowState::Fresh, content),
+            WindowState::Working
+        );
+    }
+
+    #[test]
+    fn test_no_interrupt_footer_keeps_recorded_state() {
+        let content = "⏺ All tests pass.\n\n> ";
+        assert!(!detect_interrupt_footer(content));
+        assert_eq!(refine_state(WindowState::Idle, content), WindowState::Idle);
+        assert_eq!(
+            refine_state(WindowState::Fresh, content),
+            WindowState::Fresh
+        );
+    }
+
+    #[test]
+    fn test_compacting_only_in_tail() {
+        let compacting = "\
+⏺ Updated the parser tests
+
+✻ Compacting conversation… (esc to interrupt)
+> ";
+        assert!(detect_compacting(compacting));
+        assert_eq!(
+            refine_state(WindowState::Working, compacting),
+            WindowState::Compacting
+        );
+        assert_eq!(
+            refine_state(WindowState::Idle, compacting),
+            WindowState::Compacting
+        );
+
+        // Finished: the line scrolled up and the recorded state stands again
+        let finished = "\
+✻ Compacting conversation…
+⏺ Compacted. ctrl+o to see the summary
+⏺ Picking up the parser work
+  ⎿  Read 3 files
+⏺ All tests pass.
+> ";
+        assert!(!detect_compacting(finished));
+        assert_eq!(refine_state(WindowState::Idle, finished), WindowState::Idle);
+    }
+
+    #[test]
+    fn test_login_steps_are_asking() {
+        let picker = "\
+ Claude Code can be used with your Claude subscription or billed based on API usage.
+
+ Select login method:
+
+   1. Claude account with subscription
+   2. Anthropic Console account";
+        let paste = "\
+ Browser didn't open? Use the url below to sign in:
+
+https://claude.ai/oauth/authorize?code=true&client_id=9d1c250a&response_type=code&redirect_
+uri=https%3A%2F%2Fconsole.anthropic.com%2Foauth%2Fcode%2Fcallback&scope=user%3Ainference&st
+ate=abc123
+
+ Paste code here if prompted >";
+        let expired = "⏺ API Error: 401 · Please run /login\n\n> ";
+        for content in [picker, paste, expired] {
+            assert!(detect_auth(content), "content: {content}");
+            assert_eq!(
+                refine_state(WindowState::Fresh, content),
+                WindowState::Asking
+            );
+        }
+        assert_eq!(
+            refine_state(WindowState::Idle, expired),
+            WindowState::Asking
+        );
+    }
+
+    #[test]
+    fn test_auth_talk_is_not_a_login_step() {
+        let content = "\
+⏺ Added authentication middleware and a log in form.
+  ⎿  Updated src/auth.rs with 40 additions
+
+> ";
+        assert!(!detect_auth(content));
+        assert_eq!(refine_state(WindowState::Idle, content), WindowState::Idle);
+
+        // A login prompt long since scrolled past doesn't count
+        let old = format!("Paste code here if prompted >\n{}", "⏺ ok\n".repeat(10));
+        assert!(!detect_auth(&old));
+    }
+
+    #[test]
+    fn test_settle_ignores_single_prompt_frame() {
+        let plain = "⏺ Writing the parser\n  ✻ Thinking…";
+        let flicker = "⏺ Writing the parser\n❯ 1. Yes";
+        let mut prompted = Some(false);
+        for frame in [plain, flicker, plain] {
+            let (state, now) = settle(WindowState::Working, Some(frame), prompted);
+            assert_eq!(state, WindowState::Working, "frame: {frame}");
+            prompted = Some(now);
+        }
+    }
+
+    #[test]
+    fn test_settle_commits_prompt_on_second_frame() {
+        let menu = "Do you trust the files in this folder?\n❯ 1. Yes, proceed";
+        let (state, prompted) = settle(WindowState::Fresh, Some(menu), Some(false));
+        assert_eq!(state, WindowState::Fresh);
+        let (state, _) = settle(WindowState::Fresh, Some(menu), Some(prompted));
+        assert_eq!(state, WindowState::Asking);
+        // A window seen for the first time has nothing to confirm against
+        let (state, _) = settle(WindowState::Fresh, Some(menu), None);
+        assert_eq!(state, WindowState::Asking);
+    }
+
+    #[test]
+    fn test_trim_capture_keeps_tail_and_cuts_width() {
+        let long = "x".repeat(CAPTURE_LINE_CHARS + 50);
+        let content = format!("one\n\ntwo\n{long}\n\n");
+        let trimmed = trim_capture(&content, 2);
+        let lines: Vec<&str> = trimmed.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], "two");
+        assert_eq!(lines[1].chars().count(), CAPTURE_LINE_CHARS);
+        // Multi-byte chars are cut on a boundary
+        let wide = "❯".repeat(CAPTURE_LINE_CHARS + 1);
+        assert_eq!(trim_capture(&wide, 1).chars().count(), CAPTURE_LINE_CHARS);
+    }
+
+    #[test]
+    fn test_trimmed_capture_still_detects() {
+        let content = "⏺ Bash(cargo test)\n\n  running 12 tests\n\n❯ 1. Yes\n  2. No\n";
+        let trimmed = trim_capture(content, TOOL_TAIL_LINES);
+        assert_eq!(detect_tool(&trimmed).as_deref(), Some("Bash"));
+        assert!(detect_question(&trimmed));
+    }
+
+    #[test]
+    fn test_detect_tool_latest_call_wins() {
+        let content = "\
+⏺ Read(src/main.rs)
+  ⎿  Read 120 lines
+⏺ Bash(cargo test --workspace)
+  ⎿  Running…
+✻ Compiling… (12s · esc to interrupt)
+";
+        assert_eq!(detect_tool(content).as_deref(), Some("Bash"));
+        assert_eq!(detect_tool("Running Grep…\n").as_deref(), Some("Grep"));
+    }
+
+    #[test]
+    fn test_detect_tool_none_for_prose() {
+        assert_eq!(detect_tool("⏺ I'll read the Bash docs first.\n"), None);
+        assert_eq!(detect_tool("Let me Read(this) later\n"), None);
+    }
+
+    #[test]
+    fn test_should_capture_between_rounds_only_when_stale() {
+        let last = Refined {
+            recorded: WindowState::Working,
+            state: WindowState::Asking,
+            tool: None,
+        };
+        assert!(should_capture(
+            WindowState::Working,
+            Some(&last),
+            true,
+            true
+        ));
+        assert!(!should_capture(
+            WindowState::Working,
+            Some(&last),
+            false,
+            true
+        ));
+        // A new hook event since the last capture
+        assert!(should_capture(WindowState::Idle, Some(&last), false, true));
+        assert!(should_capture(WindowState::Idle, None, false, true));
+        // Nothing to refine
+        assert!(!should_capture(WindowState::Asking, None, true, true));
+    }
+
+    #[test]
+    fn test_should_capture_never_when_hidden() {
+        assert!(!should_capture(WindowState::Working, None, true, false));
+        assert!(!should_capture(WindowState::Idle, None, false, false));
+    }
+
+    #[test]
+    fn test_track_since_keeps_first_seen_time() {
+        let start = Instant::now();
+        let later = start + Duration::from_secs(5);
+        let mut since = HashMap::new();
+        let pane = |id: &str| id.to_string();
+
+        let states = HashMap::from([
+            (pane("%1"), WindowState::Done),
+            (pane("%2"), WindowState::Idle),
+        ]);
+        track_since(&mut since, &states, start);
+        track_since(&mut since, &states, later);
+        assert_eq!(since["%1"], (WindowState::Done, start));
+        assert_eq!(since["%2"], (WindowState::Idle, start));
+
+        // A state change resets the clock; panes that went away are dropped
+        let states = HashMap::from([(pane("%1"), WindowState::Working)]);
+        track_since(&mut since, &states, later);
+        assert_eq!(
+            since,
+            HashMap::from([(pane("%1"), (WindowState::Working, later))])
+        );
+    }
+
+    #[test]
+    fn test_claude_exited_defaults() {
+        let detect = DetectConfig::default();
+        assert!(claude_exited("zsh", &detect));
+        assert!(claude_exited("fish", &detect));
+        assert!(claude_exited("python3", &detect));
+        assert!(!claude_exited("claude", &detect));
+        assert!(!claude_exited("node", &detect));
+        assert!(!claude_exited("2.0.14", &detect));
+    }
+
+    #[test]
+    fn test_claude_exited_custom_sets() {
+        let detect = DetectConfig {
+            done_commands: vec!["nu".to_string()],
+            claude_commands: vec!["bun".to_string()],
+            ..DetectConfig::default()
+        };
+        assert!(claude_exited("nu", &detect));
+        assert!(claude_exited("node", &detect));
+        assert!(!claude_exited("bun", &detect));
+        // No claude-like list: only the done list counts
+        let detect = DetectConfig {
+            claude_commands: Vec::new(),
+            ..DetectConfig::default()
+        };
+        assert!(claude_exited("bash", &detect));
+        assert!(!claude_exited("python3", &detect));
+    }
+
     #[test]
     fn test_state_from_str() {
         assert_eq!(state_from_str("working"), WindowState::Working);
+        assert_eq!(state_from_str("ask-done"), WindowState::Working);
         assert_eq!(state_from_str("idle"), WindowState::Idle);
         assert_eq!(state_from_str("asking"), WindowState::Asking);
         assert_eq!(state_from_str("unknown"), WindowState::Fresh);
@@ -1,69 +1,187 @@
 // ── ratatui rendering for sidebar ──
 
 use std::collections::HashMap;
+use std::time::Duration;
 
+use crossterm::event::KeyCode;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
 use ratatui::style::{Color, Modifier, Style};
+use ratatui::symbols::border;
 use ratatui::text::{Line, Span};
-use ratatui::widgets::Widget;
+use ratatui::widgets::{Block, Borders, Clear, Widget};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
-use crate::colors;
+use crate::colors::Theme;
+use crate::config::{Config, Spinner, StatusLabels};
+use crate::paths;
+use crate::sidebar::event::{KeySpec, Keymap};
 use crate::sidebar::state::WindowState;
 use crate::tmux::WindowInfo;
 
 // ── Types ──
 
-/// Legend entry for keyboard shortcuts.
-struct LegendEntry {
-    key: &'static str,
-    label: &'static str,
+/// Characters the sidebar draws with. `ASCII` stands in for terminals (serial
+/// consoles, some SSH clients) that render the Unicode set as boxes.
+pub struct Glyphs {
+    selected: &'static str,
+    /// Marks the window tmux has focused when the cursor is elsewhere.
+    active: &'static str,
+    bullet: &'static str,
+    rule: char,
+    up: &'static str,
+    down: &'static str,
+    ellipsis: &'static str,
+    spinner: &'static [&'static str],
+    border: border::Set,
 }
 
-const LEGEND: &[LegendEntry] = &[
-    LegendEntry {
-        key: "\u{2318} + j",
-        label: "claude",
-    },
-    LegendEntry {
-        key: "\u{2318} + m",
-        label: "terminal",
-    },
-    LegendEntry {
-        key: "\u{2318} + p",
-        label: "sessions",
-    },
-    LegendEntry {
-        key: "\u{2318} + ;",
-        label: "exit",
+pub const UNICODE: Glyphs = Glyphs {
+    selected: "\u{276f}",
+    active: "\u{25cf}",
+    bullet: "\u{00b7}",
+    rule: '\u{2500}',
+    up: "\u{2191}",
+    down: "\u{2193}",
+    ellipsis: "\u{2026}",
+    spinner: BRAILLE,
+    border: border::PLAIN,
+};
+
+pub const ASCII: Glyphs = Glyphs {
+    selected: ">",
+    active: "@",
+    bullet: "*",
+    rule: '-',
+    up: "^",
+    down: "v",
+    ellipsis: "...",
+    spinner: LINE,
+    border: border::Set {
+        top_left: "+",
+        top_right: "+",
+        bottom_left: "+",
+        bottom_right: "+",
+        vertical_left: "|",
+        vertical_right: "|",
+        horizontal_top: "-",
+        horizontal_bottom: "-",
     },
-];
+};
+
+const BRAILLE: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+const DOTS: &[&str] = &["⣾", "⣽", "⣻", "⢿", "⡿", "⣟", "⣯", "⣷"];
+const LINE: &[&str] = &["|", "/", "-", "\\"];
+
+/// Ticks each half of the Asking pulse lasts (about half a second).
+const BLINK_TICKS: u64 = 5;
+const ARC: &[&str] = &["◜", "◠", "◝", "◞", "◡", "◟"];
 
 pub struct SidebarWidget<'a> {
     pub windows: &'a [WindowInfo],
     pub states: &'a HashMap<u32, WindowState>,
+    /// Tool each Working window is running, shown after the spinner.
+    pub tools: &'a HashMap<u32, String>,
+    /// Last prompt sent to each window, shown dimmed on the row below its name.
+    pub titles: &'a HashMap<u32, String>,
     pub selected: usize,
+    /// How long the selected window has been in its current state, for the footer.
+    pub elapsed: Option<Duration>,
     pub tick: u64,
+    /// Working spinner frames, from `spinner_frames`.
+    pub spinner: &'a [String],
+    /// Pulse Asking rows between two colors (`sidebar.blink_asking`).
+    pub blink_asking: bool,
+    /// Head each run of windows sharing a directory with that directory and
+    /// indent them beneath it (`sidebar.group_by_dir`). Headers are drawn
+    /// only; `selected` still indexes `windows`.
+    pub group_by_dir: bool,
+    /// Status text overrides (`sidebar.labels`).
+    pub labels: &'a StatusLabels,
+    pub show_help: bool,
+    /// Filter query being typed, shown in the header in place of the hint.
+    pub filter: Option<&'a str>,
+    pub theme: &'a Theme,
+    pub glyphs: &'a Glyphs,
+    /// Sidebar keybindings, listed in the help overlay.
+    pub keys: &'a Keymap,
+    /// Config problem shown in the footer in place of the selected session.
+    pub warning: Option<&'a str>,
 }
 
 // ── Public API ──
 
+impl Glyphs {
+    /// `$CCS_ASCII` when set, otherwise `sidebar.ascii` from the config file.
+    pub fn load() -> &'static Glyphs {
+        let ascii = match std::env::var("CCS_ASCII") {
+            Ok(v) if !v.is_empty() => v != "0" && !v.eq_ignore_ascii_case("false"),
+            _ => Config::load().sidebar.ascii,
+        };
+        if ascii { &ASCII } else { &UNICODE }
+    }
+}
+
+/// Frames for the Working spinner: the configured preset or frame list, else
+/// the glyph set's own. Unknown presets and empty lists warn on stderr and
+/// fall back to the default.
+pub fn spinner_frames(config: Option<&Spinner>, g: &Glyphs) -> Vec<String> {
+    let frames: &[&str] = match config {
+        None => g.spinner,
+        Some(Spinner::Frames(frames)) if !frames.is_empty() => return frames.clone(),
+        Some(Spinner::Frames(_)) => {
+            eprintln!("ccs: sidebar.spinner has no frames, using the default");
+            g.spinner
+        }
+        Some(Spinner::Preset(name)) => match name.to_ascii_lowercase().as_str() {
+            "braille" => BRAILLE,
+            "dots" => DOTS,
+            "line" => LINE,
+            "arc" => ARC,
+            _ => {
+                eprintln!("ccs: unknown spinner '{name}', using the default");
+                g.spinner
+            }
+        },
+    };
+    frames.iter().map(|f| f.to_string()).collect()
+}
+
 impl Widget for SidebarWidget<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
         let window_count = self.windows.len();
+        let g = self.glyphs;
 
         // ── Header ──
         let plural = if window_count == 1 { "" } else { "s" };
-        let header = Line::from(vec![
+        let mut header = vec![
             Span::raw(" "),
             Span::styled(
                 format!("{window_count} session{plural}"),
-                Style::default().fg(colors::OVERLAY),
+                Style::default().fg(self.theme.overlay),
             ),
-            Span::styled(" \u{00b7} ", Style::default().fg(colors::SURFACE)),
-            Span::styled("\u{2191}\u{2193}", Style::default().fg(colors::BLUE)),
-            Span::styled(" navigate", Style::default().fg(colors::OVERLAY)),
-        ]);
+            Span::styled(
+                format!(" {} ", g.bullet),
+                Style::default().fg(self.theme.surface),
+            ),
+        ];
+        match self.filter {
+            Some(query) => {
+                header.push(Span::styled("/", Style::default().fg(self.theme.blue)));
+                header.push(Span::styled(query, Style::default().fg(Color::White)));
+            }
+            None => {
+                header.push(Span::styled(
+                    format!("{}{}", g.up, g.down),
+                    Style::default().fg(self.theme.blue),
+                ));
+                header.push(Span::styled(
+                    " navigate",
+                    Style::default().fg(self.theme.overlay),
+                ));
+            }
+        }
+        let header = Line::from(header);
         if area.height > 0 {
             buf.set_line(area.x, area.y, &header, area.width);
         }
@@ -73,111 +191,744 @@ impl Widget for SidebarWidget<'_> {
             let sep_row = area.y + 1;
             for x in area.x..area.x + area.width {
                 buf.cell_mut((x, sep_row))
-                    .map(|cell| cell.set_char('\u{2500}').set_fg(colors::SURFACE));
+                    .map(|cell| cell.set_char(g.rule).set_fg(self.theme.surface));
             }
         }
 
+        // ── Footer: a config warning, or the selected session's name,
+        // directory, and state ──
+        // Only when there's room for it below at least a couple of body rows
+        let selected = self.windows.get(self.selected);
+        let footer =
+            area.height >= 5 && !self.show_help && (self.warning.is_some() || selected.is_some());
+        let body_end = area.y + area.height - u16::from(footer);
+        if let Some(warning) = self.warning.filter(|_| footer) {
+            let line = Line::from(vec![
+                Span::raw(" "),
+                Span::styled(warning, Style::default().fg(self.theme.yellow)),
+            ]);
+            buf.set_line(area.x, body_end, &line, area.width);
+        } else if let Some(win) = selected.filter(|_| footer) {
+            let state = self
+                .states
+                .get(&win.index)
+                .copied()
+                .unwrap_or(WindowState::Fresh);
+            // The row's status text, labels included; states that show none
+            // there still get named here
+            let mut status = status_text(state, g, self.labels);
+            if status.is_empty() {
+                status = state.name().to_string();
+            }
+            if let Some(elapsed) = self.elapsed {
+                status = format!("{status} {}", format_elapsed(elapsed));
+            }
+            let sep = || {
+                Span::styled(
+                    format!(" {} ", g.bullet),
+                    Style::default().fg(self.theme.surface),
+                )
+            };
+            let line = Line::from(vec![
+                Span::raw(" "),
+                Span::styled(win.name.clone(), Style::default().fg(self.theme.overlay)),
+                sep(),
+                Span::styled(
+                    paths::tilde(&win.pane_path),
+                    Style::default().fg(self.theme.surface),
+                ),
+                sep(),
+                Span::styled(status, Style::default().fg(self.theme.overlay)),
+            ]);
+            buf.set_line(area.x, body_end, &line, area.width);
+        }
+
         // ── Body: sessions (left) + legend (right) ──
         let body_start = area.y + 2;
-        let max_rows = window_count.max(LEGEND.len());
 
         // Calculate right column start (for legend)
         let right_col = area.width.saturating_sub(15);
 
-        #[allow(clippy::needless_range_loop)] // indexes two parallel arrays of different lengths
-        for row in 0..max_rows {
-            let y = body_start + row as u16;
-            if y >= area.y + area.height {
-                break;
+        // Left column: session list, with each session's last prompt beneath it
+        let indent = if self.group_by_dir { 2 } else { 0 };
+        let cols = (right_col as usize).saturating_sub(indent);
+        let mut session_lines = Vec::new();
+        for (row, win) in self.windows.iter().enumerate() {
+            let new_group = row == 0 || self.windows[row - 1].pane_path != win.pane_path;
+            if self.group_by_dir && new_group {
+                session_lines.push(Line::from(vec![
+                    Span::raw(" "),
+                    Span::styled(
+                        truncate(&paths::tilde(&win.pane_path), cols, g),
+                        Style::default().fg(self.theme.surface),
+                    ),
+                ]));
             }
 
-            // Left column: session list
-            if row < window_count {
-                let win = &self.windows[row];
-                let state = self
-                    .states
-                    .get(&win.index)
-                    .copied()
-                    .unwrap_or(WindowState::Fresh);
-                let is_selected = row == self.selected;
-
-                let (bullet, name_style) = if is_selected {
-                    (
-                        Span::styled("\u{276f}", Style::default().fg(Color::White)),
-                        Style::default()
-                            .fg(Color::White)
-                            .add_modifier(Modifier::BOLD),
-                    )
-                } else {
-                    (
-                        Span::styled("\u{00b7}", Style::default().fg(colors::OVERLAY)),
-                        Style::default().fg(colors::OVERLAY),
-                    )
-                };
+            let state = self
+                .states
+                .get(&win.index)
+                .copied()
+                .unwrap_or(WindowState::Fresh);
+            let is_selected = row == self.selected;
+            let pulse = (self.blink_asking && state == WindowState::Asking)
+                .then(|| asking_color(self.tick, self.theme));
 
-                let mut spans = vec![
-                    Span::raw(" "),
-                    bullet,
-                    Span::raw(" "),
-                    Span::styled(&win.name, name_style),
-                ];
-
-                let status = status_text(state);
-                if matches!(state, WindowState::Working) {
-                    // Spinner renders inline right after the name
-                    spans.push(status_span(state, self.tick));
-                } else if !status.is_empty() {
-                    // Right-align status text against the legend column
-                    let name_width = 3 + win.name.len(); // " · " or " ❯ " prefix + name
-                    let status_width = status.chars().count() + 2; // 2 spaces before status
-                    let pad = (right_col as usize).saturating_sub(name_width + status_width);
-                    spans.push(Span::raw(" ".repeat(pad)));
-                    spans.push(status_span(state, self.tick));
+            let (bullet, name_style) = if is_selected {
+                (
+                    Span::styled(g.selected, Style::default().fg(Color::White)),
+                    Style::default()
+                        .fg(Color::White)
+                        .add_modifier(Modifier::BOLD),
+                )
+            } else if win.is_active {
+                (
+                    Span::styled(g.active, Style::default().fg(self.theme.peach)),
+                    Style::default().fg(pulse.unwrap_or(self.theme.overlay)),
+                )
+            } else {
+                (
+                    Span::styled(g.bullet, Style::default().fg(self.theme.overlay)),
+                    Style::default().fg(pulse.unwrap_or(self.theme.overlay)),
+                )
+            };
+
+            // Clamp the name so it and its status stay clear of the legend
+            let status = status_text(state, g, self.labels);
+            let tool = self
+                .tools
+                .get(&win.index)
+                .filter(|_| state == WindowState::Working);
+            let reserved = match state {
+                WindowState::Working => 2 + tool.map_or(0, |t| t.width() + 1), // " ⠋ Bash"
+                _ if !status.is_empty() => 1 + status.width() + 2,             // gap, status, gap
+                _ => 1,
+            };
+            let name = truncate(&win.name, cols.saturating_sub(3 + reserved), g);
+
+            let mut spans = vec![
+                Span::raw(" ".repeat(1 + indent)),
+                bullet,
+                Span::raw(" "),
+                Span::styled(name.clone(), name_style),
+            ];
+
+            if matches!(state, WindowState::Working) {
+                // Spinner renders inline right after the name, then the tool
+                spans.push(status_span(
+                    state,
+                    status.clone(),
+                    self.tick,
+                    self.theme,
+                    self.spinner,
+                ));
+                if let Some(tool) = tool {
+                    spans.push(Span::styled(
+                        format!(" {tool}"),
+                        Style::default().fg(self.theme.overlay),
+                    ));
                 }
+            } else if !status.is_empty() {
+                // Right-align status text against the legend column
+                // Display columns, not bytes: CJK names and "…" would misalign
+                let name_width = 3 + name.width(); // " · " or " ❯ " prefix + name
+                let status_width = status.width() + 2; // 2 spaces before status
+                let pad = cols.saturating_sub(name_width + status_width);
+                spans.push(Span::raw(" ".repeat(pad)));
+                let status =
+                    status_span(state, status.clone(), self.tick, self.theme, self.spinner);
+                spans.push(match pulse {
+                    Some(color) => status.patch_style(Style::default().fg(color)),
+                    None => status,
+                });
+            }
+            session_lines.push(Line::from(spans));
 
-                let line = Line::from(spans);
-                buf.set_line(area.x, y, &line, right_col);
+            if let Some(title) = self.titles.get(&win.index) {
+                // Indented under the name, leaving a column of gap before the legend
+                let width = cols.saturating_sub(4);
+                session_lines.push(Line::from(vec![
+                    Span::raw(" ".repeat(3 + indent)),
+                    Span::styled(
+                        truncate(title, width, g),
+                        Style::default()
+                            .fg(self.theme.surface)
+                            .add_modifier(Modifier::ITALIC),
+                    ),
+                ]));
+            }
+        }
+
+        let legend = legend_entries(g, self.keys);
+        let max_rows = session_lines.len().max(legend.len());
+        for row in 0..max_rows {
+            let y = body_start + row as u16;
+            if y >= body_end {
+                break;
+            }
+
+            if let Some(line) = session_lines.get(row) {
+                buf.set_line(area.x, y, line, right_col);
             }
 
             // Right column: legend
-            if row < LEGEND.len() {
-                let entry = &LEGEND[row];
+            if let Some((key, label)) = legend.get(row) {
                 let legend_line = Line::from(vec![
-                    Span::styled(entry.key, Style::default().fg(colors::BLUE)),
+                    Span::styled(key.clone(), Style::default().fg(self.theme.blue)),
                     Span::raw("  "),
-                    Span::styled(entry.label, Style::default().fg(colors::OVERLAY)),
+                    Span::styled(*label, Style::default().fg(self.theme.overlay)),
                 ]);
                 buf.set_line(area.x + right_col, y, &legend_line, area.width - right_col);
             }
         }
+
+        // ── Empty state: centered hint in the session column ──
+        if window_count == 0 {
+            let body_height = body_end.saturating_sub(body_start);
+            if body_height > 0 {
+                let y = body_start + body_height / 2;
+                let text = match self.filter {
+                    Some(_) => "No matches".to_string(),
+                    None => empty_hint(g),
+                };
+                let hint_width = text.chars().count() as u16;
+                let x = area.x + right_col.saturating_sub(hint_width) / 2;
+                let hint = Line::from(Span::styled(
+                    text,
+                    Style::default()
+                        .fg(self.theme.overlay)
+                        .add_modifier(Modifier::ITALIC),
+                ));
+                buf.set_line(x, y, &hint, right_col.saturating_sub(x - area.x));
+            }
+        }
+
+        if self.show_help {
+            render_help(area, buf, self.theme, g, self.keys);
+        }
     }
 }
 
 // ── Helpers ──
 
-const SPINNER: &[char] = &['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+/// Draw the keybinding overlay centered in `area`, clipped to fit.
+fn render_help(area: Rect, buf: &mut Buffer, theme: &Theme, g: &Glyphs, keys: &Keymap) {
+    let help = help_entries(g, keys);
+    let key_width = help
+        .iter()
+        .map(|(key, _)| key.chars().count())
+        .max()
+        .unwrap_or(0);
+    let label_width = help
+        .iter()
+        .map(|(_, label)| label.chars().count())
+        .max()
+        .unwrap_or(0);
+    let width = ((key_width + label_width + 6) as u16).min(area.width);
+    let height = (help.len() as u16 + 2).min(area.height);
+    if width < 3 || height < 3 {
+        return;
+    }
+
+    let rect = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+    Clear.render(rect, buf);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(g.border)
+        .border_style(Style::default().fg(theme.surface))
+        .title(Span::styled(" keys ", Style::default().fg(theme.overlay)));
+    let inner = block.inner(rect);
+    block.render(rect, buf);
+
+    for (i, (key, label)) in help.iter().take(inner.height as usize).enumerate() {
+        let line = Line::from(vec![
+            Span::raw(" "),
+            Span::styled(
+                format!("{key:<key_width$}"),
+                Style::default().fg(theme.blue),
+            ),
+            Span::raw("  "),
+            Span::styled(*label, Style::default().fg(theme.overlay)),
+        ]);
+        buf.set_line(inner.x, inner.y + i as u16, &line, inner.width);
+    }
+}
+
+/// The configured keys for the main actions, listed beside the sessions.
+fn legend_entries(g: &Glyphs, keys: &Keymap) -> [(String, &'static str); 5] {
+    [
+        (key_labels(&keys.up, g), "up"),
+        (key_labels(&keys.down, g), "down"),
+        (key_labels(&keys.select, g), "open"),
+        (key_labels(&keys.help, g), "help"),
+        (key_labels(&keys.quit, g), "quit"),
+    ]
+}
+
+/// Keys handled inside the sidebar, shown in the `?` overlay.
+fn help_entries(g: &Glyphs, keys: &Keymap) -> [(String, &'static str); 8] {
+    [
+        (key_labels(&keys.up, g), "previous session"),
+        (key_labels(&keys.down, g), "next session"),
+        ("^u / ^d".to_string(), "half page up / down"),
+        ("g / G".to_string(), "first / last session"),
+        (key_labels(&keys.select, g), "jump to session"),
+        ("/".to_string(), "filter by name"),
+        (key_labels(&keys.quit, g), "quit sidebar"),
+        (key_labels(&keys.help, g), "toggle help"),
+    ]
+}
+
+/// Keys bound to one action, as shown in the help overlay (e.g. "↑ / k").
+fn key_labels(specs: &[KeySpec], g: &Glyphs) -> String {
+    let label = |spec: &KeySpec| {
+        let name = match spec.code {
+            KeyCode::Up => g.up.to_string(),
+            KeyCode::Down => g.down.to_string(),
+            KeyCode::Char(' ') => "space".to_string(),
+            KeyCode::Char(c) => c.to_string(),
+            KeyCode::PageUp => "pgup".to_string(),
+            KeyCode::PageDown => "pgdn".to_string(),
+            code => code.to_string().to_lowercase(),
+        };
+        if spec.ctrl { format!("^{name}") } else { name }
+    };
+    specs.iter().map(label).collect::<Vec<_>>().join(" / ")
+}
+
+fn empty_hint(g: &Glyphs) -> String {
+    format!("No sessions {} run ccs start", g.bullet)
+}
+
+/// Cut `text` to at most `width` display columns, ending in an ellipsis when shortened.
+fn truncate(text: &str, width: usize, g: &Glyphs) -> String {
+    if text.width() <= width {
+        return text.to_string();
+    }
+    let budget = width.saturating_sub(g.ellipsis.width());
+    let mut out = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w > budget {
+            break;
+        }
+        used += w;
+        out.push(c);
+    }
+    out.push_str(g.ellipsis);
+    out
+}
+
+/// Compact duration for the footer: "0m42s", or "1h05m" from an hour up.
+fn format_elapsed(elapsed: Duration) -> String {
+    let secs = elapsed.as_secs();
+    match secs / 3600 {
+        0 => format!("{}m{:02}s", secs / 60, secs % 60),
+        hours => format!("{hours}h{:02}m", secs % 3600 / 60),
+    }
+}
+
+/// Asking rows alternate between peach and yellow, a step every `BLINK_TICKS`.
+fn asking_color(tick: u64, theme: &Theme) -> Color {
+    if (tick / BLINK_TICKS).is_multiple_of(2) {
+        theme.peach
+    } else {
+        theme.yellow
+    }
+}
 
-fn status_text(state: WindowState) -> &'static str {
+/// Text shown right-aligned for a state: the configured label, else the built-in one.
+fn status_text(state: WindowState, g: &Glyphs, labels: &StatusLabels) -> String {
+    let custom = match state {
+        WindowState::Working => None,
+        WindowState::Asking => labels.asking.as_ref(),
+        WindowState::Limited => labels.limited.as_ref(),
+        WindowState::Idle => labels.idle.as_ref(),
+        WindowState::Done => labels.done.as_ref(),
+        WindowState::Fresh => labels.fresh.as_ref(),
+        WindowState::Compacting => labels.compacting.as_ref(),
+    };
+    if let Some(text) = custom {
+        return text.clone();
+    }
     match state {
-        WindowState::Working => "",
-        WindowState::Asking => "waiting\u{2026}",
-        WindowState::Idle => "",
-        WindowState::Done => "done",
-        WindowState::Fresh => "",
+        WindowState::Working => String::new(),
+        WindowState::Asking => format!("waiting{}", g.ellipsis),
+        WindowState::Limited => "limited".to_string(),
+        WindowState::Idle => String::new(),
+        WindowState::Done => "done".to_string(),
+        WindowState::Fresh => String::new(),
+        WindowState::Compacting => format!("compacting{}", g.ellipsis),
     }
 }
 
-fn status_span(state: WindowState, tick: u64) -> Span<'static> {
+/// The status cell for a row: the spinner for Working, else `text` styled by state.
+fn status_span(
+    state: WindowState,
+    text: String,
+    tick: u64,
+    theme: &Theme,
+    spinner: &[String],
+) -> Span<'static> {
     match state {
         WindowState::Working => {
-            let frame = SPINNER[tick as usize % SPINNER.len()];
-            Span::styled(format!(" {frame}"), Style::default().fg(colors::LAVENDER))
+            let frame = match spinner.len() {
+                0 => "",
+                n => &spinner[tick as usize % n],
+            };
+            Span::styled(format!(" {frame}"), Style::default().fg(theme.lavender))
         }
+        WindowState::Limited => Span::styled(
+            text,
+            Style::default()
+                .fg(theme.red)
+                .add_modifier(Modifier::ITALIC),
+        ),
+        WindowState::Compacting => Span::styled(
+            text,
+            Style::default()
+                .fg(theme.lavender)
+                .add_modifier(Modifier::ITALIC),
+        ),
         _ => Span::styled(
-            status_text(state),
+            text,
             Style::default()
-                .fg(colors::OVERLAY)
+                .fg(theme.overlay)
                 .add_modifier(Modifier::ITALIC),
         ),
     }
 }
+
+// ── Tests ──
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colors;
+    use crate::tmux::test_window;
+    use std::sync::LazyLock;
+
+    static NO_TEXT: LazyLock<HashMap<u32, String>> = LazyLock::new(HashMap::new);
+    static LABELS: LazyLock<StatusLabels> = LazyLock::new(StatusLabels::default);
+    static KEYS: LazyLock<Keymap> = LazyLock::new(Keymap::default);
+
+    /// A widget over `windows` with everything else at its default, for tests
+    /// to override only what they exercise.
+    fn widget<'a>(
+        windows: &'a [WindowInfo],
+        states: &'a HashMap<u32, WindowState>,
+    ) -> SidebarWidget<'a> {
+        SidebarWidget {
+            windows,
+            states,
+            tools: &NO_TEXT,
+            titles: &NO_TEXT,
+            selected: 0,
+            elapsed: None,
+            tick: 0,
+            spinner: &[],
+            blink_asking: false,
+            group_by_dir: false,
+            labels: &LABELS,
+            show_help: false,
+            filter: None,
+            theme: &colors::MOCHA,
+            glyphs: &UNICODE,
+            keys: &KEYS,
+            warning: None,
+        }
+    }
+
+    fn row_text(buf: &Buffer, y: u16) -> String {
+        (0..buf.area.width)
+            .map(|x| buf[(x, y)].symbol())
+            .collect::<String>()
+    }
+
+    #[test]
+    fn test_empty_state_shows_hint_and_legend() {
+        let area = Rect::new(0, 0, 60, 8);
+        let mut buf = Buffer::empty(area);
+        let states = HashMap::new();
+        widget(&[], &states).render(area, &mut buf);
+
+        let rows: Vec<String> = (0..area.height).map(|y| row_text(&buf, y)).collect();
+        assert!(rows[0].contains("0 sessions"));
+        assert!(rows.iter().any(|r| r.contains(&empty_hint(&UNICODE))));
+        assert!(rows.iter().any(|r| r.contains("enter  open")));
+        assert!(rows.iter().any(|r| r.contains("q  quit")));
+    }
+
+    #[test]
+    fn test_legend_lists_configured_keys() {
+        let keys = Keymap {
+            quit: vec![KeySpec {
+                code: KeyCode::Char('x'),
+                ctrl: true,
+            }],
+            ..Keymap::default()
+        };
+        let legend = legend_entries(&UNICODE, &keys);
+        assert!(legend.contains(&("^x".to_string(), "quit")));
+        assert!(legend.contains(&("\u{2193} / j".to_string(), "down")));
+    }
+
+    #[test]
+    fn test_help_overlay_stays_in_bounds() {
+        let area = Rect::new(0, 0, 20, 5);
+        let mut buf = Buffer::empty(area);
+        let states = HashMap::new();
+        // Buffer indexing panics on out-of-bounds writes
+        SidebarWidget {
+            show_help: true,
+            ..widget(&[], &states)
+        }
+        .render(area, &mut buf);
+
+        let rows: Vec<String> = (0..area.height).map(|y| row_text(&buf, y)).collect();
+        assert!(rows.iter().any(|r| r.contains("keys")));
+    }
+
+    #[test]
+    fn test_ascii_glyphs_render_ascii_only() {
+        let area = Rect::new(0, 0, 80, 12);
+        let mut buf = Buffer::empty(area);
+        let states = HashMap::new();
+        SidebarWidget {
+            show_help: true,
+            glyphs: &ASCII,
+            ..widget(&[], &states)
+        }
+        .render(area, &mut buf);
+
+        let rows: Vec<String> = (0..area.height).map(|y| row_text(&buf, y)).collect();
+        assert!(rows.iter().all(|r| r.is_ascii()));
+        assert!(rows.iter().any(|r| r.contains("^ / k  up")));
+    }
+
+    #[test]
+    fn test_title_renders_truncated_below_name() {
+        let area = Rect::new(0, 0, 40, 8);
+        let mut buf = Buffer::empty(area);
+        let windows = [WindowInfo {
+            is_active: true,
+            ..test_window(1, "api")
+        }];
+        let titles = HashMap::from([(1, "refactor the session loader to stream".to_string())]);
+        SidebarWidget {
+            titles: &titles,
+            ..widget(&windows, &HashMap::new())
+        }
+        .render(area, &mut buf);
+
+        assert!(row_text(&buf, 2).contains("api"));
+        let subtitle = row_text(&buf, 3);
+        assert!(subtitle.starts_with("   refactor the session"));
+        assert!(subtitle.contains('\u{2026}'));
+    }
+
+    #[test]
+    fn test_spinner_frames_presets_and_fallbacks() {
+        assert_eq!(spinner_frames(None, &ASCII), ["|", "/", "-", "\\"]);
+        let arc = Spinner::Preset("Arc".to_string());
+        assert_eq!(spinner_frames(Some(&arc), &UNICODE).len(), ARC.len());
+        let custom = Spinner::Frames(vec![".".to_string(), "o".to_string()]);
+        assert_eq!(spinner_frames(Some(&custom), &UNICODE), [".", "o"]);
+        let empty = Spinner::Frames(Vec::new());
+        assert_eq!(spinner_frames(Some(&empty), &UNICODE).len(), BRAILLE.len());
+        let unknown = Spinner::Preset("moon".to_string());
+        assert_eq!(
+            spinner_frames(Some(&unknown), &UNICODE).len(),
+            BRAILLE.len()
+        );
+    }
+
+    #[test]
+    fn test_key_labels() {
+        let keys = Keymap::default();
+        assert_eq!(key_labels(&keys.up, &UNICODE), "\u{2191} / k");
+        assert_eq!(key_labels(&keys.select, &ASCII), "enter");
+        let ctrl_n = KeySpec {
+            code: KeyCode::Char('n'),
+            ctrl: true,
+        };
+        assert_eq!(key_labels(&[ctrl_n], &ASCII), "^n");
+    }
+
+    #[test]
+    fn test_status_aligns_with_wide_names() {
+        let status_column = |name: &str| {
+            let area = Rect::new(0, 0, 40, 6);
+            let mut buf = Buffer::empty(area);
+            let windows = [WindowInfo {
+                is_active: true,
+                ..test_window(1, name)
+            }];
+            let states = HashMap::from([(1, WindowState::Asking)]);
+            widget(&windows, &states).render(area, &mut buf);
+            (0..area.width).position(|x| buf[(x, 2)].symbol() == "w")
+        };
+
+        let ascii = status_column("api-test");
+        assert!(ascii.is_some());
+        assert_eq!(status_column("日本語作業"), ascii);
+    }
+
+    #[test]
+    fn test_long_name_stays_out_of_legend() {
+        let area = Rect::new(0, 0, 40, 6);
+        let mut buf = Buffer::empty(area);
+        let windows = [WindowInfo {
+            is_active: true,
+            ..test_window(1, "a-very-long-directory-derived-session-name")
+        }];
+        let states = HashMap::from([(1, WindowState::Done)]);
+        widget(&windows, &states).render(area, &mut buf);
+
+        let row = row_text(&buf, 2);
+        assert!(row.contains('\u{2026}'));
+        assert!(row.contains("\u{2026} done"));
+        assert!(row.trim_end().ends_with("\u{2191} / k  up"));
+    }
+
+    #[test]
+    fn test_footer_shows_selected_session() {
+        let area = Rect::new(0, 0, 60, 8);
+        let mut buf = Buffer::empty(area);
+        let windows = [WindowInfo {
+            is_active: true,
+            pane_path: "/srv/app".to_string(),
+            ..test_window(1, "review")
+        }];
+        let states = HashMap::from([(1, WindowState::Working)]);
+        SidebarWidget {
+            elapsed: Some(Duration::from_secs(42)),
+            ..widget(&windows, &states)
+        }
+        .render(area, &mut buf);
+
+        let footer = row_text(&buf, area.height - 1);
+        assert_eq!(
+            footer.trim_end(),
+            " review \u{b7} /srv/app \u{b7} working 0m42s"
+        );
+
+        let labels = StatusLabels {
+            asking: Some("needs you".to_string()),
+            ..StatusLabels::default()
+        };
+        let states = HashMap::from([(1, WindowState::Asking)]);
+        let mut buf = Buffer::empty(area);
+        SidebarWidget {
+            labels: &labels,
+            ..widget(&windows, &states)
+        }
+        .render(area, &mut buf);
+        let footer = row_text(&buf, area.height - 1);
+        assert!(footer.trim_end().ends_with("\u{b7} needs you"));
+    }
+
+    #[test]
+    fn test_footer_shows_config_warning() {
+        let area = Rect::new(0, 0, 60, 8);
+        let mut buf = Buffer::empty(area);
+        let windows = [test_window(1, "review")];
+        SidebarWidget {
+            warning: Some("invalid key 'bogus' for keys.down"),
+            ..widget(&windows, &HashMap::new())
+        }
+        .render(area, &mut buf);
+
+        let footer = row_text(&buf, area.height - 1);
+        assert_eq!(footer.trim_end(), " invalid key 'bogus' for keys.down");
+    }
+
+    #[test]
+    fn test_asking_color_alternates() {
+        let theme = &colors::MOCHA;
+        assert_eq!(asking_color(0, theme), theme.peach);
+        assert_eq!(asking_color(BLINK_TICKS - 1, theme), theme.peach);
+        assert_eq!(asking_color(BLINK_TICKS, theme), theme.yellow);
+        assert_eq!(asking_color(2 * BLINK_TICKS, theme), theme.peach);
+    }
+
+    #[test]
+    fn test_group_by_dir_heads_each_directory() {
+        let area = Rect::new(0, 0, 60, 10);
+        let mut buf = Buffer::empty(area);
+        let window = |index: u32, dir: &str| WindowInfo {
+            pane_path: dir.to_string(),
+            ..test_window(index, &format!("s{index}"))
+        };
+        let windows = [
+            window(1, "/srv/a"),
+            window(2, "/srv/a"),
+            window(3, "/srv/b"),
+        ];
+        SidebarWidget {
+            selected: 2,
+            group_by_dir: true,
+            ..widget(&windows, &HashMap::new())
+        }
+        .render(area, &mut buf);
+
+        let rows: Vec<String> = (2..7).map(|y| row_text(&buf, y)).collect();
+        assert!(rows[0].starts_with(" /srv/a "));
+        assert!(rows[1].starts_with("   \u{b7} s1"));
+        assert!(rows[2].starts_with("   \u{b7} s2"));
+        assert!(rows[3].starts_with(" /srv/b "));
+        assert!(rows[4].starts_with("   \u{276f} s3"));
+    }
+
+    #[test]
+    fn test_status_text_labels_override_defaults() {
+        let labels = StatusLabels {
+            idle: Some("ready".to_string()),
+            done: Some(String::new()),
+            ..StatusLabels::default()
+        };
+        assert_eq!(status_text(WindowState::Idle, &UNICODE, &labels), "ready");
+        assert_eq!(status_text(WindowState::Done, &UNICODE, &labels), "");
+        assert_eq!(
+            status_text(WindowState::Asking, &ASCII, &labels),
+            "waiting..."
+        );
+    }
+
+    #[test]
+    fn test_format_elapsed() {
+        assert_eq!(format_elapsed(Duration::from_secs(42)), "0m42s");
+        assert_eq!(format_elapsed(Duration::from_secs(754)), "12m34s");
+        assert_eq!(format_elapsed(Duration::from_secs(3900)), "1h05m");
+    }
+
+    #[test]
+    fn test_active_window_marked_apart_from_cursor() {
+        let area = Rect::new(0, 0, 40, 6);
+        let mut buf = Buffer::empty(area);
+        let window = |index: u32, is_active| WindowInfo {
+            is_active,
+            ..test_window(index, &format!("s{index}"))
+        };
+        let windows = [window(1, false), window(2, true)];
+        widget(&windows, &HashMap::new()).render(area, &mut buf);
+
+        assert!(row_text(&buf, 2).starts_with(" \u{276f} s1"));
+        assert!(row_text(&buf, 3).starts_with(" \u{25cf} s2"));
+        assert_eq!(buf[(1, 3)].fg, colors::MOCHA.peach);
+    }
+
+    #[test]
+    fn test_truncate_keeps_short_text() {
+        assert_eq!(truncate("short", 10, &UNICODE), "short");
+        assert_eq!(truncate("abcdefghij", 6, &ASCII), "abc...");
+        // Wide characters count two columns each
+        assert_eq!(truncate("日本語作業", 6, &UNICODE), "日本\u{2026}");
+    }
+}
@@ -1,20 +1,81 @@
 // ── tmux Command wrappers ──
+//
+// Every call goes through `command()`, so $CCS_TMUX can point ccs at a custom
+// tmux build or a fake one in tests.
 
+use std::ffi::OsString;
 use std::process::Command;
 
+use crate::config::{Layout, Side, SidebarConfig};
+
 // ── Types ──
 
+/// A session window, described by its Claude pane (.1).
+#[derive(Clone)]
 pub struct WindowInfo {
+    /// Real tmux window index. Killing a middle window leaves a gap (0, 2, 3)
+    /// unless `renumber_on_kill` is set, so never treat it as a list position.
     pub index: u32,
+    /// Stable tmux window id (e.g. "@4"); unlike the index it survives renumbering.
+    pub window_id: String,
     pub name: String,
     pub is_active: bool,
     pub pane_path: String,
+    /// Unique tmux pane identifier of the Claude pane (e.g. "%0", "%3").
+    pub pane_id: String,
+    /// Foreground command of the Claude pane (e.g. "claude", "zsh").
+    pub command: String,
+    /// The Claude pane's process has exited. With `remain-on-exit on` the pane
+    /// stays up showing "Pane is dead", and `command` may still read "claude".
+    pub dead: bool,
+}
+
+/// A live claude window for tests: id "@N" and pane "%N" follow the index.
+#[cfg(test)]
+pub(crate) fn test_window(index: u32, name: &str) -> WindowInfo {
+    WindowInfo {
+        index,
+        window_id: format!("@{index}"),
+        name: name.to_string(),
+        is_active: false,
+        pane_path: "/tmp".to_string(),
+        pane_id: format!("%{index}"),
+        command: "claude".to_string(),
+        dead: false,
+    }
+}
+
+/// Which pane of a window plays which part, by pane id (e.g. "%3").
+#[derive(Default, Debug, PartialEq)]
+struct PaneRoles {
+    claude: Option<String>,
+    terminal: Option<String>,
+    sidebar: Option<String>,
 }
 
 // ── Helpers ──
 
+/// The tmux executable: `$CCS_TMUX` when set and non-empty, else `tmux` on PATH.
+fn binary(var: Option<OsString>) -> OsString {
+    var.filter(|v| !v.is_empty())
+        .unwrap_or_else(|| OsString::from("tmux"))
+}
+
+fn command() -> Command {
+    Command::new(binary(std::env::var_os("CCS_TMUX")))
+}
+
 fn tmux(args: &[&str]) -> std::io::Result<std::process::Output> {
-    Command::new("tmux").args(args).output()
+    command().args(args).output()
+}
+
+/// Describe a failure to run tmux at all, singling out a missing binary.
+fn spawn_error(e: std::io::Error) -> String {
+    if e.kind() == std::io::ErrorKind::NotFound {
+        "tmux is not installed or not on PATH".to_string()
+    } else {
+        format!("tmux: {e}")
+    }
 }
 
 fn tmux_ok(args: &[&str]) -> bool {
@@ -22,7 +83,7 @@ fn tmux_ok(args: &[&str]) -> bool {
 }
 
 fn tmux_stdout(args: &[&str]) -> Result<String, String> {
-    let output = tmux(args).map_err(|e| format!("tmux: {e}"))?;
+    let output = tmux(args).map_err(spawn_error)?;
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         return Err(format!("tmux: {}", stderr.trim()));
@@ -30,96 +91,481 @@ fn tmux_stdout(args: &[&str]) -> Result<String, String> {
     Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
-// ── Public API ──
+/// Whether a tmux error means there's no server at all: "no server running",
+/// or a socket that was never created ("error connecting to ... (No such
+/// file or directory)"). Permission and other socket errors don't count.
+fn is_no_server(error: &str) -> bool {
+    error.contains("no server running")
+        || (error.contains("error connecting to") && error.contains("No such file or directory"))
+}
 
-pub const SESSION: &str = "ccs";
+/// The session option ccs sets on sessions it creates.
+const MANAGED_OPTION: &str = "@ccs_managed";
 
-pub fn has_session() -> bool {
-    tmux_ok(&["has-session", "-t", SESSION])
-}
+/// The window option holding the directory a window was started in.
+const DIR_OPTION: &str = "@ccs_dir";
+
+/// Field separator for `list_windows`. Unlike `|`, it can't turn up in a
+/// path or window name.
+const FIELD_SEP: char = '\x1f';
 
-pub fn list_windows() -> Result<Vec<WindowInfo>, String> {
+/// `list_windows` format, fields joined by `FIELD_SEP`.
+const WINDOWS_FORMAT: &str = "#{window_index}\x1f#{window_id}\x1f#{pane_at_top}\x1f#{window_active}\x1f#{pane_id}\x1f#{pane_current_command}\x1f#{pane_dead}\x1f#{pane_current_path}\x1f#{pane_start_command}\x1f#{window_name}";
+
+/// `pane-died` hook: restart the pane in its window's recorded directory.
+/// A dead pane's `pane_current_path` can't be read back from its process, and
+/// not every tmux falls back to the start directory on its own. Windows from
+/// before the option existed expand it to "", which tmux treats as unset.
+const RESPAWN_HOOK: &str = "respawn-pane -c \"#{@ccs_dir}\"";
+
+/// Some(marked) when the session exists, None when it doesn't. `=name:` makes
+/// the name match exactly instead of as a prefix of another session's name.
+/// Sessions from before the marker existed are recognized by their sidebar
+/// panes and marked on first sight.
+fn session_marker(session: &str) -> Option<bool> {
+    let target = format!("={session}:");
     let out = tmux_stdout(&[
-        "list-windows",
+        "has-session",
+        "-t",
+        &target,
+        ";",
+        "show-options",
+        "-q",
+        "-v",
+        "-t",
+        &target,
+        MANAGED_OPTION,
+    ])
+    .ok()?;
+    if out.trim() == "1" {
+        return Some(true);
+    }
+    let legacy = tmux_stdout(&[
+        "list-panes",
+        "-s",
         "-t",
-        SESSION,
+        &target,
         "-F",
-        "#{window_index}|#{window_name}|#{window_active}|#{pane_current_path}",
-    ])?;
+        "#{pane_start_command}",
+    ])
+    .is_ok_and(|out| out.lines().any(is_sidebar_command));
+    Some(legacy && tmux_ok(&["set-option", "-t", &target, MANAGED_OPTION, "1"]))
+}
+
+/// Whether a `#{pane_start_command}` is the ccs sidebar (`ccs sidebar`, as
+/// tmux may have quoted it).
+fn is_sidebar_command(cmd: &str) -> bool {
+    cmd.trim_end_matches('"').ends_with(" sidebar")
+}
+
+/// Whether a pane can be its window's claude pane, from its `#{pane_at_top}`
+/// and `#{pane_start_command}`: the sidebar is recognised by its command and
+/// claude is the other pane along the top. This holds for either side and
+/// layout, a window missing panes, and any `pane-base-index`.
+fn is_claude_pane(at_top: &str, start_command: &str) -> bool {
+    at_top == "1" && !is_sidebar_command(start_command)
+}
 
-    let mut windows = Vec::new();
+/// Pane ids of a window's claude, terminal and sidebar panes, from
+/// `list-panes -F '#{pane_id}|#{pane_at_top}|#{pane_start_command}'`. The
+/// first pane `is_claude_pane` accepts is claude; whatever's left besides the
+/// sidebar is the terminal.
+fn parse_pane_roles(out: &str) -> PaneRoles {
+    let mut roles = PaneRoles::default();
     for line in out.lines() {
-        let parts: Vec<&str> = line.splitn(4, '|').collect();
-        if parts.len() < 4 {
+        let parts: Vec<&str> = line.splitn(3, '|').collect();
+        if parts.len() < 3 {
             continue;
         }
-        windows.push(WindowInfo {
+        let id = Some(parts[0].to_string());
+        if is_sidebar_command(parts[2]) {
+            roles.sidebar = roles.sidebar.or(id);
+        } else if is_claude_pane(parts[1], parts[2]) && roles.claude.is_none() {
+            roles.claude = id;
+        } else {
+            roles.terminal = roles.terminal.or(id);
+        }
+    }
+    roles
+}
+
+/// Pane roles of the window `target` names (`""` for the current one).
+fn pane_roles(target: &str) -> Result<PaneRoles, String> {
+    let mut args = vec!["list-panes"];
+    if !target.is_empty() {
+        args.extend(["-t", target]);
+    }
+    args.extend(["-F", "#{pane_id}|#{pane_at_top}|#{pane_start_command}"]);
+    Ok(parse_pane_roles(&tmux_stdout(&args)?))
+}
+
+/// Focus `pane` of `window_id` in one tmux call, or just the window when the
+/// pane couldn't be found.
+fn select_window_pane(window_id: &str, pane: Option<String>) -> Result<(), String> {
+    let mut args = vec!["select-window", "-t", window_id];
+    if let Some(pane) = &pane {
+        args.extend([";", "select-pane", "-t", pane]);
+    }
+    let status = command().args(&args).status().map_err(spawn_error)?;
+
+    if !status.success() {
+        return Err("tmux select-window failed".to_string());
+    }
+    Ok(())
+}
+
+/// Focus the current window's pane chosen by `role`.
+fn select_pane_by_role(role: impl FnOnce(PaneRoles) -> Option<String>) -> Result<(), String> {
+    let pane = role(pane_roles("")?).ok_or("tmux: no such pane in this window")?;
+    if !tmux_ok(&["select-pane", "-t", &pane]) {
+        return Err("tmux select-pane failed".to_string());
+    }
+    Ok(())
+}
+
+/// Pane targets for the terminal and sidebar while a layout is being built.
+/// tmux numbers panes in layout
+/// order, so a sidebar split off before the terminal (left, or above when
+/// stacked) takes `.2`. Both layouts keep claude at `.1`.
+fn bottom_panes(side: Side) -> (&'static str, &'static str) {
+    match side {
+        Side::Right => (".2", ".3"),
+        Side::Left => (".3", ".2"),
+    }
+}
+
+/// What the terminal pane runs: `sidebar.terminal_command`, or nothing (so
+/// tmux starts its default-shell) when it's unset or empty.
+fn terminal_command(layout: &SidebarConfig) -> Option<&str> {
+    layout
+        .terminal_command
+        .as_deref()
+        .filter(|c| !c.trim().is_empty())
+}
+
+/// Height of the terminal area under the claude pane. Stacked layouts give it
+/// more room since the sidebar shares it as a full-width row.
+fn bottom_percent(layout: &SidebarConfig) -> &'static str {
+    match layout.layout {
+        Layout::Columns => "25",
+        Layout::Stacked => "40",
+    }
+}
+
+/// `split-window` flags that carve the sidebar out of the terminal pane.
+fn sidebar_split_args(layout: &SidebarConfig) -> Vec<String> {
+    let direction = match layout.layout {
+        Layout::Columns => "-h",
+        Layout::Stacked => "-v",
+    };
+    let mut args = vec![direction.to_string()];
+    if layout.side == Side::Left {
+        args.push("-b".to_string());
+    }
+    args.push("-p".to_string());
+    args.push(layout.percent.clamp(1, 99).to_string());
+    args
+}
+
+/// tmux rewrites '.' and ':' in session names and the name is passed to the
+/// sidebar on its command line, so keep to a conservative character set.
+fn validate_session(name: &str) -> Result<(), String> {
+    if name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        Ok(())
+    } else {
+        Err(format!(
+            "invalid session name '{name}': use letters, digits, '-' or '_'"
+        ))
+    }
+}
+
+/// Quote `arg` for the shell tmux runs pane commands through.
+fn shell_quote(arg: &str) -> String {
+    let safe = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:@,+%".contains(c));
+    if safe {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+/// Protect text passed as a single tmux argument. tmux treats an argument
+/// ending in `;` as a command separator unless the `;` is escaped as `\;`.
+fn literal_arg(text: &str) -> String {
+    match text.strip_suffix(';') {
+        Some(rest) => format!("{rest}\\;"),
+        None => text.to_string(),
+    }
+}
+
+/// Window name without the status glyph the sidebar may have prefixed.
+fn base_name(name: &str) -> &str {
+    [WORKING_GLYPH, ASKING_GLYPH]
+        .iter()
+        .find_map(|glyph| {
+            name.strip_prefix(glyph)
+                .and_then(|rest| rest.strip_prefix(' '))
+        })
+        .unwrap_or(name)
+}
+
+// ── Public API ──
+
+/// Window-name prefixes for `sidebar.window_status`.
+pub const WORKING_GLYPH: &str = "\u{2733}";
+pub const ASKING_GLYPH: &str = "\u{25c6}";
+
+pub const DEFAULT_SESSION: &str = "ccs";
+
+/// Session to operate on: `--session`, then `$CCS_SESSION`, then "ccs".
+/// Each session is an independent set of windows with its own sidebars. Event
+/// files stay in one shared directory; they're keyed by pane id, so sessions
+/// never read each other's state.
+pub fn resolve_session(flag: Option<String>) -> Result<String, String> {
+    let name = flag
+        .or_else(|| std::env::var("CCS_SESSION").ok())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| DEFAULT_SESSION.to_string());
+    validate_session(&name)?;
+    Ok(name)
+}
+
+/// Version string from `tmux -V`, or None if tmux can't be run.
+pub fn version() -> Option<String> {
+    tmux_stdout(&["-V"]).ok().map(|v| v.trim().to_string())
+}
+
+/// Whether a ccs-managed session by this name exists. A session of the same
+/// name without the `@ccs_managed` marker is someone else's; see
+/// `is_foreign_session`.
+pub fn has_session(session: &str) -> bool {
+    session_marker(session) == Some(true)
+}
+
+/// Whether a tmux session by this name exists but wasn't created by ccs.
+pub fn is_foreign_session(session: &str) -> bool {
+    session_marker(session) == Some(false)
+}
+
+/// List every window with its Claude pane's ID, command, and path.
+/// One `list-panes` call covers what used to take a `list-windows` plus a
+/// `list-panes` per sidebar tick.
+pub fn list_windows(session: &str) -> Result<Vec<WindowInfo>, String> {
+    let out = tmux_stdout(&["list-panes", "-s", "-t", session, "-F", WINDOWS_FORMAT])?;
+    Ok(parse_windows(&out))
+}
+
+fn parse_windows(out: &str) -> Vec<WindowInfo> {
+    let mut windows: Vec<WindowInfo> = Vec::new();
+    // Whether the last entry is its window's claude pane rather than a stand-in
+    let mut found = false;
+    for line in out.lines() {
+        let parts: Vec<&str> = line.splitn(10, FIELD_SEP).collect();
+        if parts.len() < 10 {
+            continue;
+        }
+        let info = WindowInfo {
             index: parts[0].parse().unwrap_or(0),
-            name: parts[1].to_string(),
-            is_active: parts[2] == "1",
-            pane_path: parts[3].to_string(),
-        });
+            window_id: parts[1].to_string(),
+            is_active: parts[3] == "1",
+            pane_id: parts[4].to_string(),
+            command: parts[5].to_string(),
+            dead: parts[6] == "1",
+            pane_path: parts[7].to_string(),
+            name: base_name(parts[9]).to_string(),
+        };
+        let claude = is_claude_pane(parts[2], parts[8]);
+
+        // One entry per window, described by its claude pane (see `is_claude_pane`).
+        // Panes arrive grouped by window; the first pane stands in until claude's shows up.
+        match windows.last_mut() {
+            Some(last) if last.index == info.index => {
+                if claude && !found {
+                    *last = info;
+                    found = true;
+                }
+            }
+            _ => {
+                windows.push(info);
+                found = claude;
+            }
+        }
     }
-    Ok(windows)
+    windows
 }
 
 /// List window names only (for duplicate checking).
-pub fn list_window_names() -> Result<Vec<String>, String> {
-    let out = tmux_stdout(&["list-windows", "-t", SESSION, "-F", "#{window_name}"])?;
-    Ok(out.lines().map(|s| s.to_string()).collect())
+pub fn list_window_names(session: &str) -> Result<Vec<String>, String> {
+    let out = tmux_stdout(&["list-windows", "-t", session, "-F", "#{window_name}"])?;
+    Ok(out.lines().map(|s| base_name(s).to_string()).collect())
+}
+
+/// Window id (`@N`) of the window containing `pane`.
+pub fn window_id_of(pane: &str) -> Result<String, String> {
+    let out = tmux_stdout(&["display-message", "-p", "-t", pane, "#{window_id}"])?;
+    Ok(out.trim().to_string())
+}
+
+/// Rename a window to `base`, prefixed with `glyph` when given.
+pub fn set_window_status_glyph(
+    window_id: &str,
+    base: &str,
+    glyph: Option<&str>,
+) -> Result<(), String> {
+    let name = match glyph {
+        Some(glyph) => format!("{glyph} {base}"),
+        None => base.to_string(),
+    };
+    tmux_stdout(&["rename-window", "-t", window_id, &name])?;
+    Ok(())
+}
+
+/// Pane IDs of every pane on the tmux server, across all sessions.
+/// Empty if no server is running; any other failure is an error, since
+/// callers treat a missing pane as gone for good.
+pub fn list_all_pane_ids() -> Result<Vec<String>, String> {
+    match tmux_stdout(&["list-panes", "-a", "-F", "#{pane_id}"]) {
+        Ok(out) => Ok(out.lines().map(|s| s.to_string()).collect()),
+        Err(e) if is_no_server(&e) => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Restart a pane whose Claude process has exited. With no command tmux reruns
+/// the pane's start command, so claude keeps the args it was started with,
+/// and the window's recorded directory stands in for the dead pane's cwd.
+pub fn respawn_claude(target: &str) -> Result<(), String> {
+    if !tmux_ok(&["respawn-pane", "-k", "-t", target, "-c", "#{@ccs_dir}"]) {
+        return Err(format!("tmux respawn-pane {target} failed"));
+    }
+    Ok(())
+}
+
+/// Type `text` into a pane verbatim (`send-keys -l`), then press Enter if asked.
+/// Key names like "C-c" and tmux separators in the text are not interpreted.
+pub fn send_keys(target: &str, text: &str, enter: bool) -> Result<(), String> {
+    if !text.is_empty() && !tmux_ok(&["send-keys", "-t", target, "-l", "--", &literal_arg(text)]) {
+        return Err(format!("tmux send-keys to {target} failed"));
+    }
+    if enter && !tmux_ok(&["send-keys", "-t", target, "Enter"]) {
+        return Err(format!("tmux send-keys to {target} failed"));
+    }
+    Ok(())
+}
+
+/// Visible contents of a pane as plain text (no escapes, wrapped lines joined).
+pub fn capture_pane(target: &str) -> Result<String, String> {
+    tmux_stdout(&["capture-pane", "-p", "-J", "-t", target])
+}
+
+/// Process id of the program running in a pane (`#{pane_pid}`).
+pub fn pane_pid(target: &str) -> Result<u32, String> {
+    let out = tmux_stdout(&["display-message", "-p", "-t", target, "#{pane_pid}"])?;
+    out.trim()
+        .parse()
+        .map_err(|_| format!("tmux: no pane pid for {target}"))
+}
+
+/// Shell command for the claude pane, with `args` quoted so they reach claude
+/// verbatim. respawn-pane reuses it when the pane dies.
+pub fn claude_command(args: &[String]) -> String {
+    std::iter::once("claude".to_string())
+        .chain(args.iter().map(|a| shell_quote(a)))
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
 pub fn is_inside_tmux() -> bool {
     std::env::var("TMUX").is_ok_and(|v| !v.is_empty())
 }
 
-pub fn new_session(name: &str, dir: &str, sidebar_bin: &str) -> Result<(), String> {
-    let status = Command::new("tmux")
-        .args([
-            "new-session",
-            "-s",
-            SESSION,
-            "-n",
-            name,
-            "-c",
-            dir,
-            ";",
-            "set-option",
-            "-w",
-            "remain-on-exit",
-            "on",
-            ";",
-            "set-hook",
-            "pane-died",
-            "respawn-pane",
-            ";",
-            "split-window",
-            "-v",
-            "-p",
-            "25",
-            "-c",
-            dir,
-            ";",
-            "split-window",
-            "-t",
-            ".2",
-            "-h",
-            "-p",
-            "30",
-            sidebar_bin,
-            ";",
-            "select-pane",
-            "-t",
-            ".2",
-            ";",
-            "respawn-pane",
-            "-t",
-            ".1",
-            "-k",
-            "claude",
-        ])
-        .status()
-        .map_err(|e| format!("tmux: {e}"))?;
+/// Size of the client running this command. Detached sessions otherwise
+/// start at tmux's 80x24 default, which skews the split percentages.
+pub fn client_size() -> Option<(u16, u16)> {
+    let out = tmux_stdout(&["display-message", "-p", "#{client_width} #{client_height}"]).ok()?;
+    let (width, height) = out.trim().split_once(' ')?;
+    Some((width.parse().ok()?, height.parse().ok()?))
+}
+
+/// Create the session with its first window laid out. `detached` leaves the
+/// caller where it is (used from inside tmux, followed by a switch-client).
+pub fn new_session(
+    session: &str,
+    name: &str,
+    dir: &str,
+    sidebar_bin: &str,
+    claude_cmd: &str,
+    layout: &SidebarConfig,
+    detached: bool,
+) -> Result<(), String> {
+    let (terminal, _) = bottom_panes(layout.side);
+    let size = if detached { client_size() } else { None };
+    let size = size.map(|(w, h)| (w.to_string(), h.to_string()));
+    let mut args = vec!["new-session"];
+    if detached {
+        args.push("-d");
+    }
+    if let Some((width, height)) = &size {
+        args.extend(["-x", width, "-y", height]);
+    }
+    args.extend([
+        "-s",
+        session,
+        "-n",
+        name,
+        "-c",
+        dir,
+        ";",
+        "set-option",
+        MANAGED_OPTION,
+        "1",
+        ";",
+        "set-option",
+        "-w",
+        "remain-on-exit",
+        "on",
+        ";",
+        "set-option",
+        "-w",
+        DIR_OPTION,
+        dir,
+        ";",
+        "set-hook",
+        "-w",
+        "pane-died",
+        RESPAWN_HOOK,
+        ";",
+        "split-window",
+        "-v",
+        "-p",
+        bottom_percent(layout),
+        "-c",
+        dir,
+    ]);
+    args.extend(terminal_command(layout));
+    args.extend([";", "split-window", "-t", ".2"]);
+    let split = sidebar_split_args(layout);
+    args.extend(split.iter().map(String::as_str));
+    args.extend([
+        sidebar_bin,
+        ";",
+        "select-pane",
+        "-t",
+        terminal,
+        ";",
+        "respawn-pane",
+        "-t",
+        ".1",
+        "-k",
+        claude_cmd,
+    ]);
+    let status = command().args(&args).status().map_err(spawn_error)?;
 
     if !status.success() {
         return Err("tmux new-session failed".to_string());
@@ -127,52 +573,56 @@ pub fn new_session(name: &str, dir: &str, sidebar_bin: &str) -> Result<(), Strin
     Ok(())
 }
 
-pub fn new_window(name: &str, dir: &str) -> Result<(), String> {
-    let status = Command::new("tmux")
-        .args(["new-window", "-t", SESSION, "-n", name, "-c", dir, "claude"])
-        .status()
-        .map_err(|e| format!("tmux: {e}"))?;
-
-    if !status.success() {
-        return Err("tmux new-window failed".to_string());
-    }
+/// Split the caller's current window to run claude beside it, titling the new
+/// pane `name`. Only meaningful from inside tmux.
+pub fn split_here(name: &str, dir: &str, claude_cmd: &str) -> Result<(), String> {
+    tmux_stdout(&[
+        "split-window",
+        "-h",
+        "-c",
+        dir,
+        claude_cmd,
+        ";",
+        "select-pane",
+        "-T",
+        name,
+    ])?;
     Ok(())
 }
 
-pub fn setup_layout(name: &str, dir: &str, sidebar_bin: &str) -> Result<(), String> {
-    let win = format!("{SESSION}:{name}");
-    let status = Command::new("tmux")
-        .args([
-            "set-option",
-            "-w",
-            "-t",
-            &win,
-            "remain-on-exit",
-            "on",
-            ";",
-            "split-window",
-            "-t",
-            &win,
-            "-v",
-            "-p",
-            "25",
-            "-c",
-            dir,
-            ";",
-            "split-window",
-            "-t",
-            &format!("{win}.2"),
-            "-h",
-            "-p",
-            "30",
-            sidebar_bin,
-            ";",
-            "select-pane",
-            "-t",
-            &format!("{win}.2"),
-        ])
-        .status()
-        .map_err(|e| format!("tmux: {e}"))?;
+/// Add a window and return its id (`@N`). Follow-up commands target the id,
+/// since a name with spaces, ':' or '.' doesn't parse as a tmux target.
+pub fn new_window(
+    session: &str,
+    name: &str,
+    dir: &str,
+    claude_cmd: &str,
+) -> Result<String, String> {
+    let out = tmux_stdout(&[
+        "new-window",
+        "-P",
+        "-F",
+        "#{window_id}",
+        "-t",
+        session,
+        "-n",
+        name,
+        "-c",
+        dir,
+        claude_cmd,
+    ])?;
+    Ok(out.trim().to_string())
+}
+
+/// Split a window made by `new_window`, addressed by its `@N` id.
+pub fn setup_layout(
+    window_id: &str,
+    dir: &str,
+    sidebar_bin: &str,
+    layout: &SidebarConfig,
+) -> Result<(), String> {
+    let args = setup_layout_args(window_id, dir, sidebar_bin, layout);
+    let status = command().args(&args).status().map_err(spawn_error)?;
 
     if !status.success() {
         return Err("tmux setup-layout failed".to_string());
@@ -180,11 +630,62 @@ pub fn setup_layout(name: &str, dir: &str, sidebar_bin: &str) -> Result<(), Stri
     Ok(())
 }
 
-pub fn attach() -> Result<(), String> {
-    let status = Command::new("tmux")
-        .args(["attach", "-t", SESSION])
+/// The tmux command chain behind `setup_layout`. `pane-died` is a window
+/// hook, so each window gets its own; one set at session creation only
+/// covers the first window.
+fn setup_layout_args(
+    window_id: &str,
+    dir: &str,
+    sidebar_bin: &str,
+    layout: &SidebarConfig,
+) -> Vec<String> {
+    let bottom = format!("{window_id}.2");
+    let (terminal, _) = bottom_panes(layout.side);
+    let terminal = format!("{window_id}{terminal}");
+    let mut args = vec![
+        "set-option",
+        "-w",
+        "-t",
+        window_id,
+        "remain-on-exit",
+        "on",
+        ";",
+        "set-option",
+        "-w",
+        "-t",
+        window_id,
+        DIR_OPTION,
+        dir,
+        ";",
+        "set-hook",
+        "-w",
+        "-t",
+        window_id,
+        "pane-died",
+        RESPAWN_HOOK,
+        ";",
+        "split-window",
+        "-t",
+        window_id,
+        "-v",
+        "-p",
+        bottom_percent(layout),
+        "-c",
+        dir,
+    ];
+    args.extend(terminal_command(layout));
+    args.extend([";", "split-window", "-t", &bottom]);
+    let split = sidebar_split_args(layout);
+    args.extend(split.iter().map(String::as_str));
+    args.extend([sidebar_bin, ";", "select-pane", "-t", &terminal]);
+    args.into_iter().map(String::from).collect()
+}
+
+pub fn attach(session: &str) -> Result<(), String> {
+    let status = command()
+        .args(["attach", "-t", session])
         .status()
-        .map_err(|e| format!("tmux: {e}"))?;
+        .map_err(spawn_error)?;
 
     if !status.success() {
         return Err("tmux attach failed".to_string());
@@ -192,11 +693,11 @@ pub fn attach() -> Result<(), String> {
     Ok(())
 }
 
-pub fn switch_client() -> Result<(), String> {
-    let status = Command::new("tmux")
-        .args(["switch-client", "-t", SESSION])
+pub fn switch_client(session: &str) -> Result<(), String> {
+    let status = command()
+        .args(["switch-client", "-t", session])
         .status()
-        .map_err(|e| format!("tmux: {e}"))?;
+        .map_err(spawn_error)?;
 
     if !status.success() {
         return Err("tmux switch-client failed".to_string());
@@ -204,93 +705,270 @@ pub fn switch_client() -> Result<(), String> {
     Ok(())
 }
 
-pub fn kill_window(name: &str) -> Result<(), String> {
-    let target = format!("{SESSION}:{name}");
-    tmux_stdout(&["kill-window", "-t", &target])?;
+/// Kill a window by name. Resolved through its id, since the live tmux name
+/// may carry a status glyph.
+pub fn kill_window(session: &str, name: &str) -> Result<(), String> {
+    let windows = list_windows(session)?;
+    let win = windows
+        .iter()
+        .find(|w| w.name == name)
+        .ok_or_else(|| format!("tmux: can't find window: {name}"))?;
+    kill_window_id(&win.window_id)
+}
+
+/// Kill a window by its tmux id (e.g. "@4").
+pub fn kill_window_id(window_id: &str) -> Result<(), String> {
+    tmux_stdout(&["kill-window", "-t", window_id])?;
     Ok(())
 }
 
-pub fn kill_session() -> Result<(), String> {
-    tmux_stdout(&["kill-session", "-t", SESSION])?;
+/// Exchange the positions of two windows, by name. Both are resolved to their
+/// ids first. tmux keeps the current index, not the current window, so the
+/// window that was active is reselected afterwards.
+pub fn swap_windows(session: &str, a: &str, b: &str) -> Result<(), String> {
+    let windows = list_windows(session)?;
+    let id_of = |name: &str| {
+        windows
+            .iter()
+            .find(|w| w.name == name)
+            .map(|w| w.window_id.clone())
+            .ok_or_else(|| format!("tmux: can't find window: {name}"))
+    };
+    let (a, b) = (id_of(a)?, id_of(b)?);
+    let mut args = vec!["swap-window", "-d", "-s", &a, "-t", &b];
+    if let Some(active) = windows.iter().find(|w| w.is_active) {
+        args.extend([";", "select-window", "-t", &active.window_id]);
+    }
+    tmux_stdout(&args)?;
     Ok(())
 }
 
-pub fn select_window(index: u32) -> Result<(), String> {
-    let target = format!("{SESSION}:{index}");
-    let status = Command::new("tmux")
-        .args([
-            "select-window",
-            "-t",
-            &target,
-            ";",
-            "select-pane",
-            "-t",
-            ":.1",
-        ])
-        .status()
-        .map_err(|e| format!("tmux: {e}"))?;
+/// Close index gaps left by killed windows (`move-window -r`).
+pub fn renumber_windows(session: &str) -> Result<(), String> {
+    tmux_stdout(&["move-window", "-r", "-t", session])?;
+    Ok(())
+}
 
-    if !status.success() {
-        return Err("tmux select-window failed".to_string());
-    }
+/// Kill a window by its tmux index, for tabs known by number rather than name.
+pub fn kill_window_by_index(session: &str, index: u32) -> Result<(), String> {
+    let windows = list_windows(session)?;
+    let win = windows
+        .iter()
+        .find(|w| w.index == index)
+        .ok_or_else(|| format!("tmux: can't find window index: {index}"))?;
+    kill_window_id(&win.window_id)
+}
+
+pub fn kill_session(session: &str) -> Result<(), String> {
+    tmux_stdout(&["kill-session", "-t", session])?;
     Ok(())
 }
 
-/// Info about pane .1 in each window (for state detection).
-pub struct PaneInfo {
-    pub window_index: u32,
-    pub command: String,
-    /// Unique tmux pane identifier (e.g. "%0", "%3").
-    pub pane_id: String,
+/// Focus the claude pane of a window, addressed by its `@N` id.
+pub fn select_window(window_id: &str) -> Result<(), String> {
+    let pane = pane_roles(window_id)?.claude;
+    select_window_pane(window_id, pane)
 }
 
-/// Get the foreground command and pane ID of pane .1 in every window.
-pub fn list_pane_commands() -> Result<Vec<PaneInfo>, String> {
-    let out = tmux_stdout(&[
-        "list-panes",
-        "-s",
-        "-t",
-        SESSION,
-        "-F",
-        "#{window_index}|#{pane_index}|#{pane_current_command}|#{pane_id}",
-    ])?;
+/// Focus the sidebar pane of a window, addressed by its `@N` id.
+pub fn select_window_sidebar(window_id: &str) -> Result<(), String> {
+    let pane = pane_roles(window_id)?.sidebar;
+    select_window_pane(window_id, pane)
+}
 
-    let mut panes = Vec::new();
-    for line in out.lines() {
-        let parts: Vec<&str> = line.splitn(4, '|').collect();
-        if parts.len() < 4 {
-            continue;
-        }
-        // Only pane index 1 (the Claude pane)
-        if parts[1] != "1" {
-            continue;
-        }
-        panes.push(PaneInfo {
-            window_index: parts[0].parse().unwrap_or(0),
-            command: parts[2].to_string(),
-            pane_id: parts[3].to_string(),
-        });
-    }
-    Ok(panes)
-}
-
-pub fn select_window_sidebar(index: u32) -> Result<(), String> {
-    let target = format!("{SESSION}:{index}");
-    let status = Command::new("tmux")
-        .args([
-            "select-window",
-            "-t",
-            &target,
-            ";",
-            "select-pane",
-            "-t",
-            ":.3",
-        ])
-        .status()
-        .map_err(|e| format!("tmux: {e}"))?;
+/// Focus the terminal pane of the current window.
+pub fn select_terminal_pane() -> Result<(), String> {
+    select_pane_by_role(|roles| roles.terminal)
+}
 
-    if !status.success() {
-        return Err("tmux select-window failed".to_string());
+/// Focus the claude pane of the current window.
+pub fn select_claude_pane() -> Result<(), String> {
+    select_pane_by_role(|roles| roles.claude)
+}
+
+/// Detach the current client, leaving every session running.
+pub fn detach_client() -> Result<(), String> {
+    if !tmux_ok(&["detach-client"]) {
+        return Err("tmux detach-client failed".to_string());
     }
     Ok(())
 }
+
+// ── Tests ──
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_binary_defaults_to_tmux() {
+        assert_eq!(binary(None), "tmux");
+        assert_eq!(binary(Some(OsString::new())), "tmux");
+        assert_eq!(
+            binary(Some(OsString::from("/opt/tmux/bin/tmux"))),
+            "/opt/tmux/bin/tmux"
+        );
+    }
+
+    #[test]
+    fn test_spawn_error_reports_missing_tmux() {
+        let err = Command::new("ccs-test-no-such-tmux")
+            .output()
+            .map_err(spawn_error)
+            .err()
+            .unwrap();
+        assert_eq!(err, "tmux is not installed or not on PATH");
+    }
+
+    #[test]
+    fn test_literal_arg_escapes_trailing_semicolon() {
+        assert_eq!(literal_arg("ls; pwd"), "ls; pwd");
+        assert_eq!(literal_arg("run it;"), "run it\\;");
+        assert_eq!(literal_arg("{"), "{");
+    }
+
+    #[test]
+    fn test_sidebar_split_args_left() {
+        let layout = SidebarConfig {
+            percent: 20,
+            side: Side::Left,
+            ..SidebarConfig::default()
+        };
+        assert_eq!(sidebar_split_args(&layout), ["-h", "-b", "-p", "20"]);
+        assert_eq!(bottom_panes(Side::Left), (".3", ".2"));
+    }
+
+    #[test]
+    fn test_is_no_server() {
+        assert!(is_no_server(
+            "tmux: no server running on /tmp/tmux-0/default"
+        ));
+        assert!(is_no_server(
+            "tmux: error connecting to /tmp/tmux-0/default (No such file or directory)"
+        ));
+        assert!(!is_no_server(
+            "tmux: error connecting to /tmp/tmux-0/default (Permission denied)"
+        ));
+        assert!(!is_no_server("tmux is not installed or not on PATH"));
+    }
+
+    #[test]
+    fn test_terminal_command_empty_means_default_shell() {
+        let with = |cmd: Option<&str>| SidebarConfig {
+            terminal_command: cmd.map(str::to_string),
+            ..SidebarConfig::default()
+        };
+        assert_eq!(terminal_command(&with(None)), None);
+        assert_eq!(terminal_command(&with(Some(" "))), None);
+        assert_eq!(terminal_command(&with(Some("lazygit"))), Some("lazygit"));
+    }
+
+    #[test]
+    fn test_sidebar_split_args_stacked() {
+        let layout = SidebarConfig {
+            layout: Layout::Stacked,
+            ..SidebarConfig::default()
+        };
+        assert_eq!(sidebar_split_args(&layout), ["-v", "-p", "30"]);
+        assert_eq!(bottom_percent(&layout), "40");
+    }
+
+    #[test]
+    fn test_resolve_session_prefers_flag() {
+        assert_eq!(resolve_session(Some("work".into())).unwrap(), "work");
+        assert!(resolve_session(Some("a:b".into())).is_err());
+        assert!(validate_session("oss_2").is_ok());
+    }
+
+    #[test]
+    fn test_setup_layout_sets_respawn_hook_per_window() {
+        let args = setup_layout_args("@3", "/code/app", "ccs sidebar", &SidebarConfig::default());
+        let hook = ["set-hook", "-w", "-t", "@3", "pane-died", RESPAWN_HOOK];
+        assert!(args.windows(hook.len()).any(|w| w == hook));
+    }
+
+    #[test]
+    fn test_claude_command_quotes_args() {
+        assert_eq!(claude_command(&[]), "claude");
+        let args = ["--model".to_string(), "opus".to_string()];
+        assert_eq!(claude_command(&args), "claude --model opus");
+        let args = ["-p".to_string(), "it's a; test".to_string()];
+        assert_eq!(claude_command(&args), r"claude -p 'it'\''s a; test'");
+    }
+
+    #[test]
+    fn test_base_name_strips_status_glyph() {
+        assert_eq!(base_name("\u{2733} review"), "review");
+        assert_eq!(base_name("\u{25c6} api"), "api");
+        assert_eq!(base_name("docs"), "docs");
+        assert_eq!(base_name("\u{2733}x"), "\u{2733}x");
+    }
+
+    #[test]
+    fn test_parse_pane_roles() {
+        let some = |id: &str| Some(id.to_string());
+
+        // Right-hand sidebar, base index 1
+        let out = "%0|1|claude\n%1|0|\n%2|0|/usr/bin/ccs sidebar\n";
+        let roles = parse_pane_roles(out);
+        assert_eq!(roles.claude, some("%0"));
+        assert_eq!(roles.terminal, some("%1"));
+        assert_eq!(roles.sidebar, some("%2"));
+
+        // Left-hand sidebar comes before the terminal in pane order
+        let out = "%4|1|claude --resume\n%6|0|\"/opt/ccs sidebar\"\n%5|0|\n";
+        let roles = parse_pane_roles(out);
+        assert_eq!(roles.claude, some("%4"));
+        assert_eq!(roles.terminal, some("%5"));
+        assert_eq!(roles.sidebar, some("%6"));
+
+        // No sidebar, and a terminal split beside claude along the top
+        let out = "%8|1|claude\n%9|1|\n";
+        assert_eq!(
+            parse_pane_roles(out),
+            PaneRoles {
+                claude: some("%8"),
+                terminal: some("%9"),
+                sidebar: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_windows_one_entry_per_window() {
+        let out = [
+            "0;@0;1;1;%0;claude;0;/code/app;claude;review",
+            "0;@0;0;1;%1;zsh;0;/code/app;;review",
+            "0;@0;1;1;%2;ccs;0;/code/app;ccs sidebar;review",
+            "2;@3;1;0;%5;claude;1;/code/a|b;claude;api|v2",
+            "3;@4;0;0;%7;claude;0;/code/docs;;docs",
+            // Sidebar on the left: it comes first, and is also along the top
+            "4;@5;1;0;%8;ccs;0;/code/ui;\"/bin/ccs sidebar\";ui",
+            "4;@5;1;0;%9;claude;0;/code/ui;claude;ui",
+            "4;@5;0;0;%10;zsh;0;/code/ui;;ui",
+        ]
+        .map(|row| row.replace(';', &FIELD_SEP.to_string()))
+        .join("\n");
+        let windows = parse_windows(&out);
+        assert_eq!(windows.len(), 4);
+
+        assert_eq!(windows[0].index, 0);
+        assert_eq!(windows[0].name, "review");
+        assert!(windows[0].is_active);
+        assert_eq!(windows[0].pane_id, "%0");
+        assert_eq!(windows[0].command, "claude");
+        assert_eq!(windows[0].pane_path, "/code/app");
+
+        assert_eq!(windows[1].index, 2);
+        assert_eq!(windows[1].window_id, "@3");
+        assert_eq!(windows[1].name, "api|v2");
+        assert_eq!(windows[1].pane_path, "/code/a|b");
+        assert!(!windows[1].is_active);
+        assert!(windows[1].dead);
+        assert!(!windows[0].dead);
+
+        // No claude pane along the top — falls back to the window's first pane
+        assert_eq!(windows[2].pane_id, "%7");
+        assert_eq!(windows[3].pane_id, "%9");
+    }
+}